@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use rust_extensions::{ApplicationStates, Logger};
+use tokio::sync::Semaphore;
+
+use crate::{
+    AggregatorEvents, AggregatorHealth, AggregatorMetrics, AggregatorMetricsSnapshot,
+    AggregatorRuntime, AggregatorSettings, AggregatorSettingsError, AggregatorState,
+    AggregatorStatus, EventsDispatcher, HealthCheck, MetricsSource,
+};
+
+use super::{
+    read_loop::ReadLoopContext, read_loop_supervisor::supervise_read_loop, BatchEngineInner,
+    CompletionStrategy, EngineMetrics, EngineStatus, QueuedRequest,
+};
+
+pub struct BatchEngine<TRequest: Send + 'static> {
+    inner: Arc<BatchEngineInner<TRequest>>,
+    logger: Arc<dyn Logger + Send + Sync + 'static>,
+    runtime: Option<Arc<dyn AggregatorRuntime + Send + Sync + 'static>>,
+    name: String,
+    settings: tokio::sync::watch::Sender<AggregatorSettings>,
+    capacity: Option<Arc<Semaphore>>,
+    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+    metrics: Arc<AggregatorMetrics>,
+    engine_metrics: Arc<EngineMetrics<TRequest>>,
+    events: EventsDispatcher,
+    state: Arc<AggregatorState>,
+    status: Arc<EngineStatus<TRequest>>,
+}
+
+impl<TRequest: Send + 'static> BatchEngine<TRequest> {
+    pub fn new(
+        name: String,
+        settings: AggregatorSettings,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Result<Self, AggregatorSettingsError> {
+        settings.validate(&name)?;
+
+        let inner = Arc::new(BatchEngineInner::new());
+
+        let metrics = Arc::new(AggregatorMetrics::new(name.clone()));
+        let engine_metrics = Arc::new(EngineMetrics::new(inner.clone(), metrics.clone()));
+        #[cfg(feature = "with-prometheus")]
+        {
+            let metrics_source: Arc<dyn MetricsSource + Send + Sync + 'static> =
+                engine_metrics.clone();
+            crate::global_metrics_registry().register(&metrics_source);
+        }
+
+        let state = Arc::new(AggregatorState::new());
+        let capacity = settings
+            .queue_capacity
+            .map(|capacity| Arc::new(Semaphore::new(capacity)));
+        let settings = tokio::sync::watch::Sender::new(settings);
+
+        let status = Arc::new(EngineStatus::new(
+            name.clone(),
+            inner.clone(),
+            state.clone(),
+            settings.subscribe(),
+            app_states.clone(),
+        ));
+        let health_check: Arc<dyn HealthCheck + Send + Sync + 'static> = status.clone();
+        crate::global_health_registry().register(&health_check);
+
+        Ok(Self {
+            inner,
+            logger,
+            runtime: crate::runtime::default_runtime(),
+            name,
+            capacity,
+            settings,
+            metrics,
+            engine_metrics,
+            events: EventsDispatcher::default(),
+            state,
+            status,
+            app_states,
+        })
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.inner.get_count()
+    }
+
+    pub fn get_aborted_attempts(&self) -> u64 {
+        self.metrics.get_aborted_attempts()
+    }
+
+    pub fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+        self.engine_metrics.get_metrics()
+    }
+
+    pub fn status(&self) -> AggregatorStatus {
+        self.status.get_status()
+    }
+
+    pub fn health(&self) -> AggregatorHealth {
+        self.status.get_health()
+    }
+
+    pub fn get_settings(&self) -> AggregatorSettings {
+        self.settings.borrow().clone()
+    }
+
+    pub fn reconfigure(&self, settings: AggregatorSettings) -> Result<(), AggregatorSettingsError> {
+        settings.validate(&self.name)?;
+
+        if settings.queue_capacity != self.settings.borrow().queue_capacity {
+            return Err(AggregatorSettingsError::QueueCapacityChanged {
+                name: self.name.clone(),
+            });
+        }
+
+        self.settings.send_replace(settings);
+        Ok(())
+    }
+
+    // The read loop gets a copy of the listeners and the runtime at start, so later
+    // changes would only be seen by producers
+    pub fn register_events(&mut self, events: Arc<dyn AggregatorEvents + Send + Sync + 'static>) {
+        if self.state.is_started() {
+            panic!(
+                "You can not register events for aggregator {} after it is started",
+                self.name
+            );
+        }
+
+        self.events.add(events);
+    }
+
+    pub fn set_runtime(&mut self, runtime: Arc<dyn AggregatorRuntime + Send + Sync + 'static>) {
+        if self.state.is_started() {
+            panic!(
+                "You can not set runtime for aggregator {} after it is started",
+                self.name
+            );
+        }
+
+        self.runtime = Some(runtime);
+    }
+
+    // False before start, after shutdown, or if the runtime dropped the read loop
+    pub fn is_running(&self) -> bool {
+        self.state.is_running()
+    }
+
+    pub fn start<TStrategy: CompletionStrategy<Request = TRequest>>(&self, strategy: TStrategy) {
+        let runtime = match &self.runtime {
+            Some(runtime) => runtime.clone(),
+            None => panic!(
+                "No runtime is set for aggregator {}. Enable with-tokio or with-smol feature or call set_runtime",
+                self.name
+            ),
+        };
+
+        if !self.state.try_set_started() {
+            panic!("You can not start aggregator {} twice", self.name);
+        }
+
+        let context = ReadLoopContext {
+            name: self.name.clone(),
+            inner: self.inner.clone(),
+            logger: self.logger.clone(),
+            runtime: runtime.clone(),
+            strategy: Arc::new(strategy),
+            settings: self.settings.subscribe(),
+            capacity: self.capacity.clone(),
+            metrics: self.metrics.clone(),
+            events: self.events.clone(),
+            state: self.state.clone(),
+        };
+
+        runtime.spawn(Box::pin(supervise_read_loop(context)));
+    }
+
+    #[cfg(any(feature = "with-tower", feature = "with-futures"))]
+    pub fn get_capacity(&self) -> Option<Arc<Semaphore>> {
+        self.capacity.clone()
+    }
+
+    pub async fn enqueue<TIter: Iterator<Item = (TRequest, usize)>>(&self, requests: TIter) {
+        self.check_not_shutting_down();
+
+        let capacity = match &self.capacity {
+            Some(capacity) => capacity,
+            None => {
+                self.push_to_queue(requests);
+                return;
+            }
+        };
+
+        let chunk_size = self.settings.borrow().queue_capacity.unwrap_or(1);
+        let mut requests = requests.peekable();
+
+        while requests.peek().is_some() {
+            let chunk: Vec<(TRequest, usize)> = requests.by_ref().take(chunk_size).collect();
+            capacity
+                .acquire_many(chunk.len() as u32)
+                .await
+                .unwrap()
+                .forget();
+            self.push_to_queue(chunk.into_iter());
+        }
+    }
+
+    // The permit is acquired by the caller in advance, e.g. from Service::poll_ready
+    #[cfg(any(feature = "with-tower", feature = "with-futures"))]
+    pub async fn enqueue_reserved(
+        &self,
+        request: TRequest,
+        items_amount: usize,
+        permit: Option<tokio::sync::OwnedSemaphorePermit>,
+    ) {
+        let permit = match permit {
+            Some(permit) => permit,
+            None => {
+                self.enqueue(std::iter::once((request, items_amount))).await;
+                return;
+            }
+        };
+
+        self.check_not_shutting_down();
+        permit.forget();
+        self.push_to_queue(std::iter::once((request, items_amount)));
+    }
+
+    fn check_not_shutting_down(&self) {
+        if self.app_states.is_shutting_down() {
+            panic!(
+                "Can not publish to aggregator {} when shutting down",
+                self.name
+            );
+        }
+    }
+
+    pub fn enqueue_blocking<TIter: Iterator<Item = (TRequest, usize)>>(&self, requests: TIter) {
+        self.check_not_shutting_down();
+
+        let capacity = match &self.capacity {
+            Some(capacity) => capacity,
+            None => {
+                self.push_to_queue(requests);
+                return;
+            }
+        };
+
+        let chunk_size = self.settings.borrow().queue_capacity.unwrap_or(1);
+        let mut requests = requests.peekable();
+
+        while requests.peek().is_some() {
+            let chunk: Vec<(TRequest, usize)> = requests.by_ref().take(chunk_size).collect();
+            crate::block_on::block_on(capacity.acquire_many(chunk.len() as u32))
+                .unwrap()
+                .forget();
+            self.push_to_queue(chunk.into_iter());
+        }
+    }
+
+    fn push_to_queue<TIter: Iterator<Item = (TRequest, usize)>>(&self, requests: TIter) {
+        let now = std::time::Instant::now();
+
+        let mut items_amount = 0;
+        let requests: Vec<_> = requests
+            .map(|(request, request_items_amount)| {
+                items_amount += request_items_amount;
+                QueuedRequest::new(request, request_items_amount, now)
+            })
+            .collect();
+
+        self.inner.push(requests);
+
+        self.metrics.inc_items_enqueued(items_amount);
+        self.events.on_enqueued(&self.name, items_amount);
+    }
+}
+
+impl<TRequest: Send + 'static> Drop for BatchEngine<TRequest> {
+    fn drop(&mut self) {
+        // The read loop publishes what is left in the queue and stops
+        self.inner.close();
+    }
+}
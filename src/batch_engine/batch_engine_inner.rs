@@ -0,0 +1,230 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::sync::{AtomicBool, AtomicUsize, Mutex, Ordering, Wakeup};
+
+use super::{IncomingStack, QueuedRequest};
+
+pub enum QueueState<TRequest: Send + 'static> {
+    Batch(Vec<QueuedRequest<TRequest>>),
+    Empty,
+    Closed,
+}
+
+pub struct QueueSnapshot {
+    pub requests: usize,
+    pub items: usize,
+    pub oldest_age: Option<Duration>,
+}
+
+// Producers only touch the lock-free incoming stack. The queue is owned by the read loop
+// and is locked only by the loop and by status, so producers never wait for each other.
+pub struct BatchEngineInner<TRequest: Send + 'static> {
+    incoming: IncomingStack<QueuedRequest<TRequest>>,
+    queue: Mutex<VecDeque<QueuedRequest<TRequest>>>,
+    count: AtomicUsize,
+    wakeup: Wakeup,
+    closed: AtomicBool,
+}
+
+impl<TRequest: Send + 'static> BatchEngineInner<TRequest> {
+    pub fn new() -> Self {
+        Self {
+            incoming: IncomingStack::new(),
+            queue: Mutex::new(VecDeque::new()),
+            count: AtomicUsize::new(0),
+            wakeup: Wakeup::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn push(&self, requests: Vec<QueuedRequest<TRequest>>) {
+        // Counted before the requests are visible, so the read loop never subtracts first
+        self.count.fetch_add(requests.len(), Ordering::SeqCst);
+
+        self.incoming.push_many(requests.into_iter());
+        self.wakeup.notify();
+    }
+
+    pub fn take_batch(&self, max_amount: usize) -> QueueState<TRequest> {
+        // Read before the queue, so requests pushed before the engine was dropped are not lost
+        let closed = self.closed.load(Ordering::SeqCst);
+
+        let mut queue = self.queue.lock().unwrap();
+        self.take_incoming(&mut queue);
+
+        if queue.is_empty() {
+            return if closed {
+                QueueState::Closed
+            } else {
+                QueueState::Empty
+            };
+        }
+
+        let amount = queue.len().min(max_amount);
+        let batch: Vec<_> = queue.drain(..amount).collect();
+
+        self.count.fetch_sub(amount, Ordering::SeqCst);
+
+        QueueState::Batch(batch)
+    }
+
+    pub fn get_snapshot(&self) -> QueueSnapshot {
+        let mut queue = self.queue.lock().unwrap();
+        self.take_incoming(&mut queue);
+
+        QueueSnapshot {
+            requests: queue.len(),
+            items: queue.iter().map(|request| request.items_amount).sum(),
+            oldest_age: queue.front().map(|request| request.created.elapsed()),
+        }
+    }
+
+    pub async fn wait_for_requests(&self) {
+        self.wakeup.wait().await;
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.wakeup.notify();
+    }
+
+    fn take_incoming(&self, queue: &mut VecDeque<QueuedRequest<TRequest>>) {
+        if !self.incoming.is_empty() {
+            queue.extend(self.incoming.take_all());
+        }
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use loom::{sync::Arc, thread};
+
+    use super::{BatchEngineInner, QueueState};
+    use crate::batch_engine::QueuedRequest;
+
+    fn check(model: impl Fn() + Sync + Send + 'static) {
+        let mut builder = loom::model::Builder::new();
+        builder.preemption_bound = Some(3);
+        builder.check(model);
+    }
+
+    fn push(inner: &BatchEngineInner<usize>, items: &[usize]) {
+        let now = std::time::Instant::now();
+        inner.push(
+            items
+                .iter()
+                .map(|item| QueuedRequest::new(*item, 1, now))
+                .collect(),
+        );
+    }
+
+    // Same protocol as the read loop: publish while there are requests, wait when the
+    // queue is empty, stop when it is closed
+    fn consume(inner: &BatchEngineInner<usize>, max_amount: usize) -> Vec<usize> {
+        let mut received = Vec::new();
+
+        loop {
+            match inner.take_batch(max_amount) {
+                QueueState::Batch(batch) => {
+                    assert!(batch.len() <= max_amount);
+                    let queued = inner.get_count();
+                    assert!(queued <= 4, "Queued amount underflow: {}", queued);
+                    received.extend(batch.into_iter().map(|request| request.request));
+                }
+                QueueState::Empty => loom::future::block_on(inner.wait_for_requests()),
+                QueueState::Closed => return received,
+            }
+        }
+    }
+
+    fn spawn_producers(
+        inner: &Arc<BatchEngineInner<usize>>,
+        batches: Vec<Vec<usize>>,
+    ) -> Vec<thread::JoinHandle<()>> {
+        batches
+            .into_iter()
+            .map(|items| {
+                let inner = inner.clone();
+                thread::spawn(move || push(&inner, &items))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_request_is_stranded() {
+        check(|| {
+            let inner = Arc::new(BatchEngineInner::new());
+
+            let consumer = {
+                let inner = inner.clone();
+                thread::spawn(move || consume(&inner, 1))
+            };
+
+            for producer in spawn_producers(&inner, vec![vec![1, 2], vec![3, 4]]) {
+                producer.join().unwrap();
+            }
+
+            // The engine is dropped only after every producer is done with it
+            inner.close();
+
+            let received = consumer.join().unwrap();
+
+            let first: Vec<usize> = received.iter().copied().filter(|i| *i <= 2).collect();
+            let second: Vec<usize> = received.iter().copied().filter(|i| *i > 2).collect();
+            assert_eq!(first, vec![1, 2]);
+            assert_eq!(second, vec![3, 4]);
+            assert_eq!(inner.get_count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_count_is_consistent_with_queue() {
+        check(|| {
+            let inner = Arc::new(BatchEngineInner::new());
+
+            let producers = spawn_producers(&inner, vec![vec![1], vec![2, 3]]);
+
+            let snapshot = inner.get_snapshot();
+            assert!(snapshot.requests <= inner.get_count());
+
+            for producer in producers {
+                producer.join().unwrap();
+            }
+
+            inner.close();
+
+            let received = consume(&inner, 2);
+            assert_eq!(received.len(), 3);
+            assert_eq!(inner.get_count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_status_does_not_steal_requests() {
+        check(|| {
+            let inner = Arc::new(BatchEngineInner::new());
+
+            let consumer = {
+                let inner = inner.clone();
+                thread::spawn(move || consume(&inner, 4))
+            };
+
+            let status = {
+                let inner = inner.clone();
+                thread::spawn(move || {
+                    inner.get_snapshot();
+                })
+            };
+
+            push(&inner, &[1, 2]);
+            status.join().unwrap();
+            inner.close();
+
+            assert_eq!(consumer.join().unwrap(), vec![1, 2]);
+        });
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+type ReservingFuture = Pin<Box<dyn Future<Output = OwnedSemaphorePermit> + Send + 'static>>;
+
+// Reserves a slot of the queue capacity in poll_ready of a Sink or a Service, so the
+// request published afterwards does not wait. Without queue_capacity it is always ready.
+pub struct CapacityReservation {
+    capacity: Option<Arc<Semaphore>>,
+    reserving: Option<ReservingFuture>,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl CapacityReservation {
+    pub fn new(capacity: Option<Arc<Semaphore>>) -> Self {
+        Self {
+            capacity,
+            reserving: None,
+            permit: None,
+        }
+    }
+
+    pub fn poll_reserve(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.permit.is_some() {
+            return Poll::Ready(());
+        }
+
+        let capacity = match &self.capacity {
+            Some(capacity) => capacity.clone(),
+            None => return Poll::Ready(()),
+        };
+
+        let reserving = self.reserving.get_or_insert_with(|| {
+            Box::pin(async move { capacity.acquire_owned().await.unwrap() })
+        });
+
+        match reserving.as_mut().poll(cx) {
+            Poll::Ready(permit) => {
+                self.reserving = None;
+                self.permit = Some(permit);
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    pub fn take_permit(&mut self) -> Option<OwnedSemaphorePermit> {
+        self.permit.take()
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt::Debug;
+
+use rust_extensions::Logger;
+
+// Strategies report callers which can not get the outcome of their batch
+pub struct CompletionReporter<'s> {
+    name: &'s str,
+    logger: &'s (dyn Logger + Send + Sync + 'static),
+}
+
+impl<'s> CompletionReporter<'s> {
+    pub fn new(name: &'s str, logger: &'s (dyn Logger + Send + Sync + 'static)) -> Self {
+        Self { name, logger }
+    }
+
+    pub fn report_unset(&self, outcome: &str, err: impl Debug) {
+        self.logger.write_error(
+            format!("round trip pusher {}", self.name),
+            format!(
+                "Can not set {} result to the task completion. {:?}",
+                outcome, err
+            ),
+            None,
+        );
+    }
+}
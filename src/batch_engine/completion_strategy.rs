@@ -0,0 +1,43 @@
+use std::{future::Future, pin::Pin};
+
+use super::CompletionReporter;
+
+pub enum BatchCompletion<TBatch> {
+    Delivered,
+    Failed(String),
+    Retry(TBatch, String),
+}
+
+// Describes how a particular aggregator turns queued requests into a callback call
+// and how the callback output is delivered back to the callers.
+pub trait CompletionStrategy: Send + Sync + 'static {
+    type Request: Send + 'static;
+    type Batch: Send + 'static;
+    type Output: Send + 'static;
+
+    fn create_batch(&self, requests: Vec<Self::Request>) -> Self::Batch;
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = Self::Output> + Send + 'static>>;
+
+    fn complete(
+        &self,
+        batch: Self::Batch,
+        output: Self::Output,
+        reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch>;
+
+    // A batch can not be retried once its items are gone for good
+    fn can_retry(&self, _batch: &Self::Batch) -> bool {
+        true
+    }
+
+    // Checked after the retry delay. Items may still be held by an aborted attempt.
+    fn items_returned(&self, _batch: &Self::Batch) -> bool {
+        true
+    }
+
+    fn drop_batch(&self, batch: Self::Batch, reason: &str, reporter: &CompletionReporter);
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use crate::{AggregatorMetrics, AggregatorMetricsSnapshot, MetricsSource};
+
+use super::BatchEngineInner;
+
+// Counters are written by the read loop, the queue depth is read from the queue
+// when the snapshot is taken. The metrics registry keeps a weak reference.
+pub struct EngineMetrics<TRequest: Send + 'static> {
+    inner: Arc<BatchEngineInner<TRequest>>,
+    metrics: Arc<AggregatorMetrics>,
+}
+
+impl<TRequest: Send + 'static> EngineMetrics<TRequest> {
+    pub fn new(inner: Arc<BatchEngineInner<TRequest>>, metrics: Arc<AggregatorMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+impl<TRequest: Send + 'static> MetricsSource for EngineMetrics<TRequest> {
+    fn get_name(&self) -> &str {
+        self.metrics.get_name()
+    }
+
+    fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+        self.metrics.get_snapshot(self.inner.get_count())
+    }
+}
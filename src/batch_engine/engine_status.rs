@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use rust_extensions::ApplicationStates;
+
+use crate::{AggregatorHealth, AggregatorSettings, AggregatorState, AggregatorStatus, HealthCheck};
+
+use super::BatchEngineInner;
+
+// Owned by the engine. The health registry keeps a weak reference, so dropped
+// aggregators disappear from the report.
+pub struct EngineStatus<TRequest: Send + 'static> {
+    name: String,
+    inner: Arc<BatchEngineInner<TRequest>>,
+    state: Arc<AggregatorState>,
+    settings: tokio::sync::watch::Receiver<AggregatorSettings>,
+    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+}
+
+impl<TRequest: Send + 'static> EngineStatus<TRequest> {
+    pub fn new(
+        name: String,
+        inner: Arc<BatchEngineInner<TRequest>>,
+        state: Arc<AggregatorState>,
+        settings: tokio::sync::watch::Receiver<AggregatorSettings>,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            name,
+            inner,
+            state,
+            settings,
+            app_states,
+        }
+    }
+
+    pub fn get_status(&self) -> AggregatorStatus {
+        let queue = self.inner.get_snapshot();
+        let config = self.settings.borrow().clone();
+
+        AggregatorStatus {
+            name: self.name.clone(),
+            started: self.state.is_started(),
+            // The engine refuses new requests once the application is shutting down
+            stopped: self.app_states.is_shutting_down(),
+            running: self.state.is_running(),
+            queued_requests: queue.requests,
+            queued_items: queue.items,
+            oldest_item_age: queue.oldest_age,
+            in_flight: self.state.get_in_flight(),
+            last_error: self.state.get_last_error(),
+            consecutive_failures: self.state.get_consecutive_failures(),
+            config,
+        }
+    }
+}
+
+impl<TRequest: Send + 'static> HealthCheck for EngineStatus<TRequest> {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_health(&self) -> AggregatorHealth {
+        AggregatorHealth::from_status(&self.get_status())
+    }
+}
@@ -0,0 +1,123 @@
+use std::ptr;
+
+use crate::sync::{AtomicPtr, Ordering};
+
+struct Node<T> {
+    value: T,
+    next: *mut Node<T>,
+}
+
+// Lock-free multi-producer stack. Consumer takes everything at once, so there is
+// no single-node pop and no ABA problem.
+pub struct IncomingStack<T> {
+    head: AtomicPtr<Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IncomingStack<T> {}
+unsafe impl<T: Send> Sync for IncomingStack<T> {}
+
+impl<T> IncomingStack<T> {
+    pub fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    // Links the items into a chain first, so the whole chain is published with one CAS
+    pub fn push_many<TIter: Iterator<Item = T>>(&self, items: TIter) {
+        let mut first: *mut Node<T> = ptr::null_mut();
+        let mut last: *mut Node<T> = ptr::null_mut();
+
+        for value in items {
+            let node = Box::into_raw(Box::new(Node { value, next: first }));
+
+            if last.is_null() {
+                last = node;
+            }
+
+            first = node;
+        }
+
+        if first.is_null() {
+            return;
+        }
+
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            unsafe {
+                (*last).next = head;
+            }
+
+            match self
+                .head
+                .compare_exchange_weak(head, first, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    // Returns items in the order they were pushed
+    pub fn take_all(&self) -> Vec<T> {
+        let mut node = self.head.swap(ptr::null_mut(), Ordering::Acquire);
+
+        let mut result = Vec::new();
+
+        while !node.is_null() {
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next;
+            result.push(boxed.value);
+        }
+
+        result.reverse();
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Drop for IncomingStack<T> {
+    fn drop(&mut self) {
+        self.take_all();
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use loom::{sync::Arc, thread};
+
+    use super::IncomingStack;
+
+    #[test]
+    fn test_concurrent_pushes_are_taken_once_in_order() {
+        loom::model(|| {
+            let stack = Arc::new(IncomingStack::new());
+
+            let producers: Vec<_> = [[1, 2], [3, 4]]
+                .into_iter()
+                .map(|items| {
+                    let stack = stack.clone();
+                    thread::spawn(move || stack.push_many(items.into_iter()))
+                })
+                .collect();
+
+            let mut taken = stack.take_all();
+
+            for producer in producers {
+                producer.join().unwrap();
+            }
+
+            taken.extend(stack.take_all());
+            assert!(stack.is_empty());
+
+            let first: Vec<i32> = taken.iter().copied().filter(|i| *i <= 2).collect();
+            let second: Vec<i32> = taken.iter().copied().filter(|i| *i > 2).collect();
+            assert_eq!(first, vec![1, 2]);
+            assert_eq!(second, vec![3, 4]);
+        });
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+// Items of the current attempt passed to start_with_lease_fn closures. They go back to
+// the batch when the lease is dropped, so a failed attempt can be retried without
+// cloning the items.
+pub struct ItemsLease<TItem: Send + 'static> {
+    items: Option<Vec<TItem>>,
+    slot: Arc<Mutex<Option<Vec<TItem>>>>,
+    #[cfg(feature = "with-telemetry")]
+    my_telemetry: Arc<my_telemetry::MyTelemetryContext>,
+}
+
+impl<TItem: Send + 'static> ItemsLease<TItem> {
+    pub(crate) fn new(
+        items: Option<Vec<TItem>>,
+        slot: Arc<Mutex<Option<Vec<TItem>>>>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: Arc<my_telemetry::MyTelemetryContext>,
+    ) -> Self {
+        Self {
+            items,
+            slot,
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+        }
+    }
+
+    pub fn get_items(&self) -> &[TItem] {
+        match &self.items {
+            Some(items) => items.as_slice(),
+            None => &[],
+        }
+    }
+
+    // Round trip pushers do not track telemetry, their batches carry an empty context
+    #[cfg(feature = "with-telemetry")]
+    pub fn get_telemetry(&self) -> &my_telemetry::MyTelemetryContext {
+        self.my_telemetry.as_ref()
+    }
+}
+
+impl<TItem: Send + 'static> Deref for ItemsLease<TItem> {
+    type Target = [TItem];
+
+    fn deref(&self) -> &[TItem] {
+        self.get_items()
+    }
+}
+
+impl<TItem: Send + 'static> Drop for ItemsLease<TItem> {
+    fn drop(&mut self) {
+        if let Some(items) = self.items.take() {
+            if let Ok(mut slot) = self.slot.lock() {
+                *slot = Some(items);
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::{Arc, Mutex};
+
+use super::ItemsLease;
+
+// Keeps the batch items between attempts. An attempt borrows the items and the lease
+// puts them back when the attempt future is dropped, including panics and aborts.
+// Unlike Arc<Vec<TItem>> this only requires TItem: Send.
+pub struct ItemsSlot<TItem: Send + 'static> {
+    items: Arc<Mutex<Option<Vec<TItem>>>>,
+}
+
+impl<TItem: Send + 'static> ItemsSlot<TItem> {
+    pub fn new(items: Vec<TItem>) -> Self {
+        Self {
+            items: Arc::new(Mutex::new(Some(items))),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.lock().unwrap().is_none()
+    }
+
+    // Leased items come back when the lease is dropped. They are lost only if nobody
+    // else holds the slot any more.
+    pub fn is_lost(&self) -> bool {
+        self.is_empty() && Arc::strong_count(&self.items) == 1
+    }
+
+    pub fn lease(
+        &self,
+        #[cfg(feature = "with-telemetry")] my_telemetry: Arc<my_telemetry::MyTelemetryContext>,
+    ) -> ItemsLease<TItem> {
+        let items = self.items.lock().unwrap().take();
+        ItemsLease::new(
+            items,
+            self.items.clone(),
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+        )
+    }
+}
+
+impl<TItem: Send + 'static> Clone for ItemsSlot<TItem> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+        }
+    }
+}
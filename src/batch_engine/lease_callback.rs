@@ -0,0 +1,9 @@
+use std::sync::Arc;
+
+use crate::runtime::RuntimeFuture;
+
+use super::ItemsLease;
+
+// Trait callbacks and closures are both called through a lease of the batch items
+pub type LeaseCallback<TItem, TOutput> =
+    Arc<dyn Fn(ItemsLease<TItem>) -> RuntimeFuture<TOutput> + Send + Sync + 'static>;
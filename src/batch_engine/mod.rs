@@ -0,0 +1,29 @@
+mod batch_engine;
+mod batch_engine_inner;
+#[cfg(any(feature = "with-tower", feature = "with-futures"))]
+mod capacity_reservation;
+mod completion_reporter;
+mod completion_strategy;
+mod engine_metrics;
+mod engine_status;
+mod incoming_stack;
+mod items_lease;
+mod items_slot;
+mod lease_callback;
+mod queued_request;
+mod read_loop;
+mod read_loop_supervisor;
+
+pub use batch_engine::*;
+pub use batch_engine_inner::*;
+#[cfg(any(feature = "with-tower", feature = "with-futures"))]
+pub use capacity_reservation::*;
+pub use completion_reporter::*;
+pub use completion_strategy::*;
+pub use engine_metrics::*;
+pub use engine_status::*;
+pub use incoming_stack::*;
+pub use items_lease::*;
+pub use items_slot::*;
+pub use lease_callback::*;
+pub use queued_request::*;
@@ -0,0 +1,19 @@
+pub struct QueuedRequest<TRequest: Send + 'static> {
+    pub request: TRequest,
+    pub items_amount: usize,
+    pub created: std::time::Instant,
+    #[cfg(feature = "with-tracing")]
+    pub span: tracing::Span,
+}
+
+impl<TRequest: Send + 'static> QueuedRequest<TRequest> {
+    pub fn new(request: TRequest, items_amount: usize, created: std::time::Instant) -> Self {
+        Self {
+            request,
+            items_amount,
+            created,
+            #[cfg(feature = "with-tracing")]
+            span: tracing::Span::current(),
+        }
+    }
+}
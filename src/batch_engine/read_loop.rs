@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use rust_extensions::Logger;
+use tokio::sync::Semaphore;
+
+use crate::{
+    runtime::CallbackTask, AggregatorEvents, AggregatorMetrics, AggregatorRuntime,
+    AggregatorSettings, AggregatorState, EventsDispatcher,
+};
+
+use super::{
+    BatchCompletion, BatchEngineInner, CompletionReporter, CompletionStrategy, QueueState,
+    QueuedRequest,
+};
+
+pub struct ReadLoopContext<TStrategy: CompletionStrategy> {
+    pub name: String,
+    pub inner: Arc<BatchEngineInner<TStrategy::Request>>,
+    pub logger: Arc<dyn Logger + Send + Sync + 'static>,
+    pub runtime: Arc<dyn AggregatorRuntime + Send + Sync + 'static>,
+    pub strategy: Arc<TStrategy>,
+    pub settings: tokio::sync::watch::Receiver<AggregatorSettings>,
+    pub capacity: Option<Arc<Semaphore>>,
+    pub metrics: Arc<AggregatorMetrics>,
+    pub events: EventsDispatcher,
+    pub state: Arc<AggregatorState>,
+}
+
+pub async fn read_loop<TStrategy: CompletionStrategy>(context: &ReadLoopContext<TStrategy>) {
+    loop {
+        let settings = context.settings.borrow().clone();
+
+        if !settings.linger.is_zero() {
+            let queued = context.inner.get_count();
+            if queued > 0 && queued < settings.max_amount_per_round_trip {
+                context.runtime.sleep(settings.linger).await;
+            }
+        }
+
+        match context.inner.take_batch(settings.max_amount_per_round_trip) {
+            QueueState::Batch(to_publish) => {
+                publish_batch(context, &settings, to_publish).await;
+            }
+            QueueState::Empty => context.inner.wait_for_requests().await,
+            QueueState::Closed => {
+                context.events.on_shutdown(&context.name);
+                break;
+            }
+        }
+    }
+}
+
+async fn publish_batch<TStrategy: CompletionStrategy>(
+    context: &ReadLoopContext<TStrategy>,
+    settings: &AggregatorSettings,
+    to_publish: Vec<QueuedRequest<TStrategy::Request>>,
+) {
+    let name = context.name.as_str();
+    let metrics = context.metrics.as_ref();
+    let events = &context.events;
+    let state = context.state.as_ref();
+    let reporter = CompletionReporter::new(name, context.logger.as_ref());
+
+    if let Some(capacity) = &context.capacity {
+        capacity.add_permits(to_publish.len());
+    }
+
+    let items_amount: usize = to_publish.iter().map(|request| request.items_amount).sum();
+
+    #[cfg(feature = "with-tracing")]
+    let batch_span = crate::batch_tracing::create_batch_span(
+        name,
+        items_amount,
+        to_publish.iter().map(|request| &request.span),
+    );
+
+    let mut requests = Vec::with_capacity(to_publish.len());
+    for queued_request in to_publish {
+        metrics
+            .time_in_queue
+            .observe_duration(queued_request.created.elapsed());
+        requests.push(queued_request.request);
+    }
+
+    let mut batch = context.strategy.create_batch(requests);
+    metrics.batch_sent(items_amount);
+
+    let mut attempt_no = 0;
+
+    let outcome = loop {
+        if attempt_no > 0 {
+            metrics.inc_retries();
+            events.on_retry(name, attempt_no + 1);
+        }
+
+        events.on_batch_started(name, items_amount, attempt_no + 1);
+        state.attempt_started(items_amount, attempt_no + 1);
+
+        let callback_future = context.strategy.create_callback_future(&mut batch);
+        #[cfg(feature = "with-tracing")]
+        let callback_future = tracing::Instrument::instrument(callback_future, batch_span.clone());
+
+        let started = std::time::Instant::now();
+        let mut task = CallbackTask::spawn(context.runtime.as_ref(), Box::pin(callback_future));
+
+        let result =
+            crate::runtime::timeout(context.runtime.as_ref(), settings.tick_timeout, &mut task)
+                .await;
+        let elapsed = started.elapsed();
+        metrics.callback_latency.observe_duration(elapsed);
+
+        attempt_no += 1;
+
+        #[cfg(feature = "with-tracing")]
+        crate::batch_tracing::record_attempt(&batch_span, attempt_no);
+
+        let (log_message, drop_reason) = match result {
+            Some(Ok(output)) => match context.strategy.complete(batch, output, &reporter) {
+                BatchCompletion::Delivered => break PublishOutcome::Delivered(elapsed),
+                BatchCompletion::Failed(reason) => break PublishOutcome::Failed(reason),
+                BatchCompletion::Retry(returned_batch, reason) => {
+                    batch = returned_batch;
+                    #[cfg(feature = "with-tracing")]
+                    crate::batch_tracing::record_outcome(&batch_span, "failed");
+
+                    let log_message = format!("Attempt {} failed. {}", attempt_no, reason);
+                    state.attempt_failed(log_message.clone());
+                    events.on_batch_failed(name, items_amount, attempt_no, reason.as_str());
+
+                    (log_message, reason)
+                }
+            },
+            Some(Err(err)) => {
+                metrics.inc_panics();
+                #[cfg(feature = "with-tracing")]
+                crate::batch_tracing::record_outcome(&batch_span, "panic");
+
+                let log_message = format!("Attempt {} panic. Err: {}", attempt_no, err);
+                state.attempt_failed(log_message.clone());
+                events.on_batch_failed(name, items_amount, attempt_no, err.as_str());
+
+                (log_message, err)
+            }
+            None => {
+                metrics.inc_timeouts();
+                #[cfg(feature = "with-tracing")]
+                crate::batch_tracing::record_outcome(&batch_span, "timeout");
+
+                let log_message = format!("Attempt {} timeout", attempt_no);
+                state.attempt_failed(log_message.clone());
+                events.on_timeout(name, attempt_no);
+
+                let aborted = crate::runtime::abort_timed_out_task(
+                    context.runtime.as_ref(),
+                    task,
+                    settings.abort_grace_period,
+                )
+                .await;
+
+                if aborted {
+                    metrics.inc_aborted_attempts();
+                }
+
+                (log_message, "Timeout".to_string())
+            }
+        };
+
+        if attempt_no >= settings.retry_policy.max_attempts {
+            context.logger.write_fatal_error(
+                format!("round trip pusher {}", name),
+                format!("Attempt {}. Skipping items", attempt_no),
+                None,
+            );
+
+            context
+                .strategy
+                .drop_batch(batch, drop_reason.as_str(), &reporter);
+            break PublishOutcome::Dropped;
+        }
+
+        // Owned batches are consumed by the callback, so there is nothing to wait for
+        if !context.strategy.can_retry(&batch) {
+            context.logger.write_fatal_error(
+                format!("round trip pusher {}", name),
+                format!(
+                    "Attempt {}. Items were consumed by the callback. Skipping items",
+                    attempt_no
+                ),
+                None,
+            );
+
+            context
+                .strategy
+                .drop_batch(batch, drop_reason.as_str(), &reporter);
+            break PublishOutcome::Dropped;
+        }
+
+        context
+            .logger
+            .write_fatal_error(format!("round trip pusher {}", name), log_message, None);
+
+        context.runtime.sleep(settings.retry_policy.delay).await;
+
+        // An aborted attempt without a grace period may still hold the items
+        if !context.strategy.items_returned(&batch) {
+            context.logger.write_fatal_error(
+                format!("round trip pusher {}", name),
+                format!(
+                    "Attempt {}. Items were not returned. Skipping items",
+                    attempt_no
+                ),
+                None,
+            );
+
+            context
+                .strategy
+                .drop_batch(batch, drop_reason.as_str(), &reporter);
+            break PublishOutcome::Dropped;
+        }
+    };
+
+    match outcome {
+        PublishOutcome::Delivered(elapsed) => {
+            state.attempt_succeeded();
+            metrics.inc_items_delivered(items_amount);
+            events.on_batch_succeeded(name, items_amount, attempt_no, elapsed);
+            #[cfg(feature = "with-tracing")]
+            crate::batch_tracing::record_outcome(&batch_span, "delivered");
+        }
+        PublishOutcome::Failed(reason) => {
+            metrics.inc_batches_failed();
+            state.attempt_failed(format!("Attempt {}. {}", attempt_no, reason));
+            events.on_batch_failed(name, items_amount, attempt_no, reason.as_str());
+            #[cfg(feature = "with-tracing")]
+            crate::batch_tracing::record_outcome(&batch_span, "failed");
+        }
+        PublishOutcome::Dropped => {
+            metrics.inc_items_dropped(items_amount);
+            events.on_items_dropped(name, items_amount);
+            #[cfg(feature = "with-tracing")]
+            crate::batch_tracing::record_outcome(&batch_span, "dropped");
+        }
+    }
+
+    state.batch_finished();
+}
+
+enum PublishOutcome {
+    Delivered(std::time::Duration),
+    Failed(String),
+    Dropped,
+}
@@ -0,0 +1,61 @@
+use std::{future::Future, sync::Arc};
+
+use crate::{runtime::CatchPanic, AggregatorEvents, AggregatorState};
+
+use super::{
+    read_loop::{read_loop, ReadLoopContext},
+    CompletionStrategy,
+};
+
+// The queue lives in the engine, so a restarted loop continues with every request which
+// was not taken yet. Only the batch which was in flight during the panic is lost.
+pub fn supervise_read_loop<TStrategy: CompletionStrategy>(
+    context: ReadLoopContext<TStrategy>,
+) -> impl Future<Output = ()> + Send + 'static {
+    // Set before the loop is spawned, so the aggregator is running as soon as start returns
+    let running = RunningGuard::new(context.state.clone());
+
+    async move {
+        let _running = running;
+
+        loop {
+            match CatchPanic::new(Box::pin(read_loop(&context))).await {
+                Ok(()) => break,
+                Err(err) => {
+                    context.metrics.inc_loop_restarts();
+                    context.state.batch_finished();
+
+                    let log_message = format!("Read loop panic. Restarting. Err: {}", err);
+                    context.state.set_last_error(log_message.clone());
+                    context.logger.write_fatal_error(
+                        format!("round trip pusher {}", context.name),
+                        log_message,
+                        None,
+                    );
+
+                    context
+                        .events
+                        .on_loop_restarted(&context.name, err.as_str());
+                }
+            }
+        }
+    }
+}
+
+// Cleared on drop as well, so the flag is reset if the runtime drops the loop
+struct RunningGuard {
+    state: Arc<AggregatorState>,
+}
+
+impl RunningGuard {
+    fn new(state: Arc<AggregatorState>) -> Self {
+        state.set_running(true);
+        Self { state }
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.state.set_running(false);
+    }
+}
@@ -0,0 +1,33 @@
+pub fn create_batch_span<'s>(
+    aggregator_name: &str,
+    batch_size: usize,
+    caller_spans: impl Iterator<Item = &'s tracing::Span>,
+) -> tracing::Span {
+    let batch_span = tracing::info_span!(
+        "aggregator_batch",
+        aggregator = aggregator_name,
+        batch_size = batch_size,
+        attempt = tracing::field::Empty,
+        outcome = tracing::field::Empty,
+    );
+
+    let mut last_id = None;
+
+    for caller_span in caller_spans {
+        let id = caller_span.id();
+        if id.is_some() && id != last_id {
+            batch_span.follows_from(id.clone());
+            last_id = id;
+        }
+    }
+
+    batch_span
+}
+
+pub fn record_attempt(batch_span: &tracing::Span, attempt_no: usize) {
+    batch_span.record("attempt", attempt_no);
+}
+
+pub fn record_outcome(batch_span: &tracing::Span, outcome: &'static str) {
+    batch_span.record("outcome", outcome);
+}
@@ -0,0 +1,40 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::Thread,
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Blocks the current thread until the future completes. Inside a tokio context the runtime
+// handle is used, which panics if called from an async task instead of dead-locking a worker.
+// Plain threads (FFI callbacks, rayon workers) are parked until the future is woken.
+pub fn block_on<TFuture: Future>(future: TFuture) -> TFuture::Output {
+    #[cfg(feature = "with-tokio")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        return handle.block_on(future);
+    }
+
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => return result,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
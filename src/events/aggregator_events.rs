@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+pub trait AggregatorEvents {
+    fn on_enqueued(&self, _aggregator_name: &str, _items_amount: usize) {}
+
+    fn on_batch_started(&self, _aggregator_name: &str, _batch_size: usize, _attempt_no: usize) {}
+
+    fn on_batch_succeeded(
+        &self,
+        _aggregator_name: &str,
+        _batch_size: usize,
+        _attempt_no: usize,
+        _duration: Duration,
+    ) {
+    }
+
+    fn on_batch_failed(
+        &self,
+        _aggregator_name: &str,
+        _batch_size: usize,
+        _attempt_no: usize,
+        _reason: &str,
+    ) {
+    }
+
+    fn on_retry(&self, _aggregator_name: &str, _attempt_no: usize) {}
+
+    fn on_timeout(&self, _aggregator_name: &str, _attempt_no: usize) {}
+
+    fn on_items_dropped(&self, _aggregator_name: &str, _amount: usize) {}
+
+    fn on_loop_restarted(&self, _aggregator_name: &str, _reason: &str) {}
+
+    fn on_shutdown(&self, _aggregator_name: &str) {}
+}
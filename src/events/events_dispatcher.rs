@@ -0,0 +1,82 @@
+use std::{sync::Arc, time::Duration};
+
+use super::AggregatorEvents;
+
+#[derive(Clone, Default)]
+pub struct EventsDispatcher {
+    listeners: Vec<Arc<dyn AggregatorEvents + Send + Sync + 'static>>,
+}
+
+impl EventsDispatcher {
+    pub fn add(&mut self, listener: Arc<dyn AggregatorEvents + Send + Sync + 'static>) {
+        self.listeners.push(listener);
+    }
+}
+
+impl AggregatorEvents for EventsDispatcher {
+    fn on_enqueued(&self, aggregator_name: &str, items_amount: usize) {
+        for listener in &self.listeners {
+            listener.on_enqueued(aggregator_name, items_amount);
+        }
+    }
+
+    fn on_batch_started(&self, aggregator_name: &str, batch_size: usize, attempt_no: usize) {
+        for listener in &self.listeners {
+            listener.on_batch_started(aggregator_name, batch_size, attempt_no);
+        }
+    }
+
+    fn on_batch_succeeded(
+        &self,
+        aggregator_name: &str,
+        batch_size: usize,
+        attempt_no: usize,
+        duration: Duration,
+    ) {
+        for listener in &self.listeners {
+            listener.on_batch_succeeded(aggregator_name, batch_size, attempt_no, duration);
+        }
+    }
+
+    fn on_batch_failed(
+        &self,
+        aggregator_name: &str,
+        batch_size: usize,
+        attempt_no: usize,
+        reason: &str,
+    ) {
+        for listener in &self.listeners {
+            listener.on_batch_failed(aggregator_name, batch_size, attempt_no, reason);
+        }
+    }
+
+    fn on_retry(&self, aggregator_name: &str, attempt_no: usize) {
+        for listener in &self.listeners {
+            listener.on_retry(aggregator_name, attempt_no);
+        }
+    }
+
+    fn on_timeout(&self, aggregator_name: &str, attempt_no: usize) {
+        for listener in &self.listeners {
+            listener.on_timeout(aggregator_name, attempt_no);
+        }
+    }
+
+    fn on_items_dropped(&self, aggregator_name: &str, amount: usize) {
+        for listener in &self.listeners {
+            listener.on_items_dropped(aggregator_name, amount);
+        }
+    }
+
+    fn on_loop_restarted(&self, aggregator_name: &str, reason: &str) {
+        for listener in &self.listeners {
+            listener.on_loop_restarted(aggregator_name, reason);
+        }
+    }
+
+    fn on_shutdown(&self, aggregator_name: &str) {
+        for listener in &self.listeners {
+            listener.on_shutdown(aggregator_name);
+        }
+    }
+}
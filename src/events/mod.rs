@@ -0,0 +1,5 @@
+mod aggregator_events;
+mod events_dispatcher;
+
+pub use aggregator_events::*;
+pub(crate) use events_dispatcher::*;
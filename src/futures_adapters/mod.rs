@@ -0,0 +1,5 @@
+mod round_trip_pusher_sink;
+mod rpc_aggregator_stream_ext;
+
+pub use round_trip_pusher_sink::*;
+pub use rpc_aggregator_stream_ext::*;
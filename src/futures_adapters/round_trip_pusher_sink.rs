@@ -0,0 +1,73 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::{batch_engine::CapacityReservation, RoundTripPusher};
+
+// Sink over a RoundTripPusher. poll_ready reserves a slot of the pusher queue capacity
+// and start_send uses it, so the sink reports not ready while the queue is full.
+pub struct RoundTripPusherSink<TItem: Send + 'static> {
+    pusher: Arc<RoundTripPusher<TItem>>,
+    reservation: CapacityReservation,
+    in_flight: Option<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
+}
+
+impl<TItem: Send + 'static> RoundTripPusherSink<TItem> {
+    pub fn new(pusher: Arc<RoundTripPusher<TItem>>) -> Self {
+        Self {
+            reservation: CapacityReservation::new(pusher.get_capacity()),
+            pusher,
+            in_flight: None,
+        }
+    }
+
+    fn poll_in_flight(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        if let Some(in_flight) = &mut self.in_flight {
+            if in_flight.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            self.in_flight = None;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<TItem: Send + 'static> futures::Sink<TItem> for RoundTripPusherSink<TItem> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        if this.poll_in_flight(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        this.reservation.poll_reserve(cx).map(Ok)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: TItem) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let pusher = this.pusher.clone();
+        let permit = this.reservation.take_permit();
+
+        this.in_flight = Some(Box::pin(async move {
+            pusher.publish_reserved(item, permit).await;
+        }));
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_in_flight(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_in_flight(cx)
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use futures::{stream::BoxStream, Stream, StreamExt};
+
+use crate::RpcAggregatorWithResult;
+
+pub trait RpcAggregatorStreamExt<TItem: Send + 'static>: Stream<Item = TItem> {
+    // Runs every item through the aggregator. Up to max_in_flight items are executed
+    // concurrently so they can share batches, and results are yielded in input order.
+    // max_in_flight of 0 is treated as 1.
+    fn execute_with<TResult: Send + 'static, TError: Send + Sync + 'static>(
+        self,
+        aggregator: Arc<RpcAggregatorWithResult<TItem, TResult, TError>>,
+        max_in_flight: usize,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> BoxStream<'static, Result<TResult, Arc<TError>>>;
+}
+
+impl<TItem: Send + 'static, TStream: Stream<Item = TItem> + Send + 'static>
+    RpcAggregatorStreamExt<TItem> for TStream
+{
+    fn execute_with<TResult: Send + 'static, TError: Send + Sync + 'static>(
+        self,
+        aggregator: Arc<RpcAggregatorWithResult<TItem, TResult, TError>>,
+        max_in_flight: usize,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> BoxStream<'static, Result<TResult, Arc<TError>>> {
+        self.map(move |item| {
+            let aggregator = aggregator.clone();
+            #[cfg(feature = "with-telemetry")]
+            let my_telemetry = my_telemetry.clone();
+
+            async move {
+                aggregator
+                    .execute_request(
+                        item,
+                        #[cfg(feature = "with-telemetry")]
+                        my_telemetry,
+                    )
+                    .await
+            }
+        })
+        .buffered(max_in_flight.max(1))
+        .boxed()
+    }
+}
@@ -0,0 +1,160 @@
+use serde::Serialize;
+
+use crate::AggregatorStatus;
+
+use super::HealthStatus;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatorHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub reasons: Vec<String>,
+}
+
+impl AggregatorHealth {
+    pub fn from_status(status: &AggregatorStatus) -> Self {
+        let mut result = Self {
+            name: status.name.clone(),
+            status: HealthStatus::Healthy,
+            reasons: Vec::new(),
+        };
+
+        let rules = &status.config.health_rules;
+
+        if !status.started {
+            result.add(
+                HealthStatus::Degraded,
+                "Aggregator is not started".to_string(),
+            );
+        } else if !status.running {
+            result.add(
+                HealthStatus::Unhealthy,
+                "Read loop is not running".to_string(),
+            );
+        }
+
+        if let (Some(max_age), Some(age)) = (rules.max_oldest_item_age, status.oldest_item_age) {
+            if age > max_age {
+                result.add(
+                    HealthStatus::Degraded,
+                    format!("Oldest item waits {:?}, max is {:?}", age, max_age),
+                );
+            }
+        }
+
+        if let Some(threshold) = rules.failure_threshold {
+            if status.consecutive_failures >= threshold {
+                result.add(
+                    rules.failure_severity,
+                    format!(
+                        "{} consecutive failures, threshold is {}",
+                        status.consecutive_failures, threshold
+                    ),
+                );
+            }
+        }
+
+        result
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthStatus::Healthy
+    }
+
+    fn add(&mut self, status: HealthStatus, reason: String) {
+        self.status = self.status.max(status);
+        self.reasons.push(reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::AggregatorHealth;
+    use crate::{AggregatorSettings, AggregatorStatus, HealthRules, HealthStatus};
+
+    fn create_status() -> AggregatorStatus {
+        let config = AggregatorSettings {
+            health_rules: HealthRules::new(
+                Some(Duration::from_secs(30)),
+                Some(3),
+                HealthStatus::Degraded,
+            ),
+            ..Default::default()
+        };
+
+        AggregatorStatus {
+            name: "test".to_string(),
+            started: true,
+            stopped: false,
+            running: true,
+            queued_requests: 1,
+            queued_items: 1,
+            oldest_item_age: Some(Duration::from_secs(1)),
+            in_flight: None,
+            last_error: None,
+            consecutive_failures: 0,
+            config,
+        }
+    }
+
+    #[test]
+    fn test_running_aggregator_is_healthy() {
+        let health = AggregatorHealth::from_status(&create_status());
+
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert!(health.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_stale_queue_and_failures_are_degraded() {
+        let mut status = create_status();
+        status.oldest_item_age = Some(Duration::from_secs(31));
+        status.consecutive_failures = 3;
+
+        let health = AggregatorHealth::from_status(&status);
+
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.reasons.len(), 2);
+    }
+
+    #[test]
+    fn test_dead_loop_is_unhealthy() {
+        let mut status = create_status();
+        status.running = false;
+        status.consecutive_failures = 5;
+
+        let health = AggregatorHealth::from_status(&status);
+
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert_eq!(health.reasons[0], "Read loop is not running");
+    }
+
+    #[test]
+    fn test_failures_get_configured_severity() {
+        let mut status = create_status();
+        status.config.health_rules.failure_severity = HealthStatus::Unhealthy;
+        status.consecutive_failures = 2;
+        assert!(AggregatorHealth::from_status(&status).is_healthy());
+
+        status.consecutive_failures = 3;
+        let health = AggregatorHealth::from_status(&status);
+
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            health.reasons,
+            vec!["3 consecutive failures, threshold is 3"]
+        );
+    }
+
+    #[test]
+    fn test_rules_are_off_by_default() {
+        let mut status = create_status();
+        status.config.health_rules = HealthRules::default();
+        status.oldest_item_age = Some(Duration::from_secs(3600));
+        status.consecutive_failures = 100;
+
+        assert!(AggregatorHealth::from_status(&status).is_healthy());
+    }
+}
@@ -0,0 +1,6 @@
+use super::AggregatorHealth;
+
+pub trait HealthCheck {
+    fn get_name(&self) -> &str;
+    fn get_health(&self) -> AggregatorHealth;
+}
@@ -0,0 +1,45 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::weak_registry::WeakRegistry;
+
+use super::{AggregatorHealth, HealthCheck, HealthReport};
+
+static GLOBAL_REGISTRY: OnceLock<HealthRegistry> = OnceLock::new();
+
+pub fn global_health_registry() -> &'static HealthRegistry {
+    GLOBAL_REGISTRY.get_or_init(HealthRegistry::new)
+}
+
+pub struct HealthRegistry {
+    items: WeakRegistry<dyn HealthCheck + Send + Sync + 'static>,
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self {
+            items: WeakRegistry::new(),
+        }
+    }
+
+    pub fn register(&self, item: &Arc<dyn HealthCheck + Send + Sync + 'static>) {
+        self.items.register(item.get_name(), item);
+    }
+
+    pub fn get_health(&self) -> Vec<AggregatorHealth> {
+        self.items
+            .get_items()
+            .iter()
+            .map(|item| item.get_health())
+            .collect()
+    }
+
+    pub fn get_report(&self) -> HealthReport {
+        HealthReport::new(self.get_health())
+    }
+}
@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+use super::{AggregatorHealth, HealthStatus};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    // The worst status of all aggregators
+    pub status: HealthStatus,
+    pub aggregators: Vec<AggregatorHealth>,
+}
+
+impl HealthReport {
+    pub fn new(aggregators: Vec<AggregatorHealth>) -> Self {
+        let status = aggregators
+            .iter()
+            .map(|aggregator| aggregator.status)
+            .max()
+            .unwrap_or(HealthStatus::Healthy);
+
+        Self {
+            status,
+            aggregators,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthStatus::Healthy
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+// Degraded aggregators still process requests and should only fail readiness.
+// Unhealthy ones stopped processing and need a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
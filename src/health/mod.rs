@@ -0,0 +1,11 @@
+mod aggregator_health;
+mod health_check;
+mod health_registry;
+mod health_report;
+mod health_status;
+
+pub use aggregator_health::*;
+pub use health_check::*;
+pub use health_registry::*;
+pub use health_report::*;
+pub use health_status::*;
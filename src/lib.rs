@@ -1,6 +1,40 @@
+mod batch_engine;
+#[cfg(feature = "with-tracing")]
+mod batch_tracing;
+mod block_on;
+mod events;
+#[cfg(feature = "with-futures")]
+mod futures_adapters;
+mod health;
+mod metrics;
+#[cfg(feature = "with-prometheus")]
+mod prometheus;
 mod round_trip_pusher;
 mod rpc_aggregator;
 mod rpc_aggregator_with_result;
+mod runtime;
+mod settings;
+mod status;
+mod sync;
+mod task_completion;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "with-tower")]
+mod tower_adapters;
+mod weak_registry;
+pub use batch_engine::ItemsLease;
+pub use events::*;
+#[cfg(feature = "with-futures")]
+pub use futures_adapters::*;
+pub use health::*;
+pub use metrics::*;
+#[cfg(feature = "with-prometheus")]
+pub use prometheus::*;
 pub use round_trip_pusher::*;
 pub use rpc_aggregator::*;
 pub use rpc_aggregator_with_result::*;
+pub use runtime::*;
+pub use settings::*;
+pub use status::*;
+#[cfg(feature = "with-tower")]
+pub use tower_adapters::*;
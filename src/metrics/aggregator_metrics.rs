@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{AggregatorMetricsSnapshot, Histogram, DURATION_BUCKETS_MICROS, SIZE_BUCKETS};
+
+pub struct AggregatorMetrics {
+    name: String,
+    items_enqueued: AtomicU64,
+    items_delivered: AtomicU64,
+    items_dropped: AtomicU64,
+    batches_sent: AtomicU64,
+    batches_failed: AtomicU64,
+    retries: AtomicU64,
+    timeouts: AtomicU64,
+    panics: AtomicU64,
+    aborted_attempts: AtomicU64,
+    loop_restarts: AtomicU64,
+    pub batch_size: Histogram,
+    pub callback_latency: Histogram,
+    pub time_in_queue: Histogram,
+}
+
+impl AggregatorMetrics {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            items_enqueued: AtomicU64::new(0),
+            items_delivered: AtomicU64::new(0),
+            items_dropped: AtomicU64::new(0),
+            batches_sent: AtomicU64::new(0),
+            batches_failed: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            timeouts: AtomicU64::new(0),
+            panics: AtomicU64::new(0),
+            aborted_attempts: AtomicU64::new(0),
+            loop_restarts: AtomicU64::new(0),
+            batch_size: Histogram::new(SIZE_BUCKETS),
+            callback_latency: Histogram::new(DURATION_BUCKETS_MICROS),
+            time_in_queue: Histogram::new(DURATION_BUCKETS_MICROS),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn inc_items_enqueued(&self, amount: usize) {
+        self.items_enqueued
+            .fetch_add(amount as u64, Ordering::Relaxed);
+    }
+
+    pub fn inc_items_delivered(&self, amount: usize) {
+        self.items_delivered
+            .fetch_add(amount as u64, Ordering::Relaxed);
+    }
+
+    pub fn inc_items_dropped(&self, amount: usize) {
+        self.items_dropped
+            .fetch_add(amount as u64, Ordering::Relaxed);
+    }
+
+    pub fn batch_sent(&self, batch_size: usize) {
+        self.batches_sent.fetch_add(1, Ordering::Relaxed);
+        self.batch_size.observe(batch_size as u64);
+    }
+
+    pub fn inc_batches_failed(&self) {
+        self.batches_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_retries(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_timeouts(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_panics(&self) {
+        self.panics.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_aborted_attempts(&self) {
+        self.aborted_attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get_aborted_attempts(&self) -> u64 {
+        self.aborted_attempts.load(Ordering::Relaxed)
+    }
+
+    pub fn inc_loop_restarts(&self) {
+        self.loop_restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // The queue depth is owned by the queue, so the caller passes the current value
+    pub fn get_snapshot(&self, queue_depth: usize) -> AggregatorMetricsSnapshot {
+        AggregatorMetricsSnapshot {
+            name: self.name.clone(),
+            queue_depth,
+            items_enqueued: self.items_enqueued.load(Ordering::Relaxed),
+            items_delivered: self.items_delivered.load(Ordering::Relaxed),
+            items_dropped: self.items_dropped.load(Ordering::Relaxed),
+            batches_sent: self.batches_sent.load(Ordering::Relaxed),
+            batches_failed: self.batches_failed.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            panics: self.panics.load(Ordering::Relaxed),
+            aborted_attempts: self.get_aborted_attempts(),
+            loop_restarts: self.loop_restarts.load(Ordering::Relaxed),
+            batch_size: self.batch_size.get_snapshot(),
+            callback_latency_micros: self.callback_latency.get_snapshot(),
+            time_in_queue_micros: self.time_in_queue.get_snapshot(),
+        }
+    }
+}
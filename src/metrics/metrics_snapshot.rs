@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramSnapshot {
+    pub bounds: Vec<u64>,
+    // One counter per bound plus the trailing overflow bucket. Counters are not cumulative.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatorMetricsSnapshot {
+    pub name: String,
+    pub queue_depth: usize,
+    pub items_enqueued: u64,
+    pub items_delivered: u64,
+    pub items_dropped: u64,
+    pub batches_sent: u64,
+    pub batches_failed: u64,
+    pub retries: u64,
+    pub timeouts: u64,
+    pub panics: u64,
+    pub aborted_attempts: u64,
+    pub loop_restarts: u64,
+    pub batch_size: HistogramSnapshot,
+    pub callback_latency_micros: HistogramSnapshot,
+    pub time_in_queue_micros: HistogramSnapshot,
+}
@@ -0,0 +1,6 @@
+use super::AggregatorMetricsSnapshot;
+
+pub trait MetricsSource {
+    fn get_name(&self) -> &str;
+    fn get_metrics(&self) -> AggregatorMetricsSnapshot;
+}
@@ -0,0 +1,9 @@
+mod aggregator_metrics;
+mod histogram;
+mod metrics_snapshot;
+mod metrics_source;
+
+pub use aggregator_metrics::*;
+pub use histogram::*;
+pub use metrics_snapshot::*;
+pub use metrics_source::*;
@@ -0,0 +1,205 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::{weak_registry::WeakRegistry, AggregatorMetricsSnapshot, MetricsSource};
+
+use super::prometheus_writer::{ExportedSnapshot, PrometheusWriter};
+
+static GLOBAL_REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+
+pub fn global_metrics_registry() -> &'static MetricsRegistry {
+    GLOBAL_REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+pub struct MetricsRegistry {
+    items: WeakRegistry<dyn MetricsSource + Send + Sync + 'static>,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            items: WeakRegistry::new(),
+        }
+    }
+
+    pub fn register(&self, metrics: &Arc<dyn MetricsSource + Send + Sync + 'static>) {
+        self.items.register(metrics.get_name(), metrics);
+    }
+
+    pub fn get_snapshots(&self) -> Vec<AggregatorMetricsSnapshot> {
+        self.items
+            .get_items()
+            .iter()
+            .map(|item| item.get_metrics())
+            .collect()
+    }
+
+    pub fn render_prometheus(&self) -> String {
+        let snapshots: Vec<ExportedSnapshot> = self
+            .items
+            .get_instances()
+            .into_iter()
+            .map(|(instance_no, item)| ExportedSnapshot::new(item.get_metrics(), instance_no))
+            .collect();
+        let mut writer = PrometheusWriter::new();
+
+        writer.write_gauge(
+            "rpc_aggregator_queue_depth",
+            "Amount of requests waiting in the queue",
+            &snapshots,
+            |snapshot| snapshot.queue_depth as u64,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_items_enqueued_total",
+            "Items published to the aggregator",
+            &snapshots,
+            |snapshot| snapshot.items_enqueued,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_items_delivered_total",
+            "Items successfully handled by the callback",
+            &snapshots,
+            |snapshot| snapshot.items_delivered,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_items_dropped_total",
+            "Items skipped after all attempts failed",
+            &snapshots,
+            |snapshot| snapshot.items_dropped,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_batches_sent_total",
+            "Batches handed to the callback",
+            &snapshots,
+            |snapshot| snapshot.batches_sent,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_batches_failed_total",
+            "Batches the callback returned an error for",
+            &snapshots,
+            |snapshot| snapshot.batches_failed,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_retries_total",
+            "Repeated callback attempts",
+            &snapshots,
+            |snapshot| snapshot.retries,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_timeouts_total",
+            "Callback attempts which exceeded tick timeout",
+            &snapshots,
+            |snapshot| snapshot.timeouts,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_panics_total",
+            "Callback attempts which panicked",
+            &snapshots,
+            |snapshot| snapshot.panics,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_aborted_attempts_total",
+            "Timed out callback attempts which were aborted",
+            &snapshots,
+            |snapshot| snapshot.aborted_attempts,
+        );
+
+        writer.write_counter(
+            "rpc_aggregator_loop_restarts_total",
+            "Read loop restarts after a panic",
+            &snapshots,
+            |snapshot| snapshot.loop_restarts,
+        );
+
+        writer.write_histogram(
+            "rpc_aggregator_batch_size",
+            "Amount of items per batch",
+            &snapshots,
+            1.0,
+            |snapshot| &snapshot.batch_size,
+        );
+
+        writer.write_histogram(
+            "rpc_aggregator_callback_latency_seconds",
+            "Duration of a callback attempt",
+            &snapshots,
+            1_000_000.0,
+            |snapshot| &snapshot.callback_latency_micros,
+        );
+
+        writer.write_histogram(
+            "rpc_aggregator_time_in_queue_seconds",
+            "Time between publishing an item and sending it within a batch",
+            &snapshots,
+            1_000_000.0,
+            |snapshot| &snapshot.time_in_queue_micros,
+        );
+
+        writer.into_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use crate::{AggregatorMetrics, AggregatorMetricsSnapshot, MetricsSource};
+
+    use super::MetricsRegistry;
+
+    struct TestSource {
+        metrics: AggregatorMetrics,
+    }
+
+    impl MetricsSource for TestSource {
+        fn get_name(&self) -> &str {
+            self.metrics.get_name()
+        }
+
+        fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+            self.metrics.get_snapshot(0)
+        }
+    }
+
+    fn create_source(name: &str) -> Arc<dyn MetricsSource + Send + Sync + 'static> {
+        Arc::new(TestSource {
+            metrics: AggregatorMetrics::new(name.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_same_names_get_unique_series() {
+        let registry = MetricsRegistry::new();
+        let first = create_source("dup");
+        let second = create_source("dup");
+        registry.register(&first);
+        registry.register(&second);
+
+        let result = registry.render_prometheus();
+
+        assert!(result.contains("rpc_aggregator_queue_depth{name=\"dup\"} 0"));
+        assert!(result.contains("rpc_aggregator_queue_depth{name=\"dup\",instance_no=\"1\"} 0"));
+
+        let series: Vec<&str> = result
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect();
+        let unique: HashSet<&str> = series.iter().copied().collect();
+        assert_eq!(series.len(), unique.len());
+    }
+}
@@ -0,0 +1,4 @@
+mod metrics_registry;
+mod prometheus_writer;
+
+pub use metrics_registry::*;
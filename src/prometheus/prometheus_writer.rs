@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use crate::{AggregatorMetricsSnapshot, HistogramSnapshot};
+
+// A snapshot with the labels of its series. Aggregators sharing a name are told apart
+// by the instance_no label, so every series has its own label set.
+pub struct ExportedSnapshot {
+    snapshot: AggregatorMetricsSnapshot,
+    labels: String,
+}
+
+impl ExportedSnapshot {
+    pub fn new(snapshot: AggregatorMetricsSnapshot, instance_no: usize) -> Self {
+        let mut labels = format!("name=\"{}\"", escape_label_value(&snapshot.name));
+        if instance_no > 0 {
+            let _ = write!(labels, ",instance_no=\"{}\"", instance_no);
+        }
+
+        Self { snapshot, labels }
+    }
+}
+
+pub struct PrometheusWriter {
+    result: String,
+}
+
+impl PrometheusWriter {
+    pub fn new() -> Self {
+        Self {
+            result: String::new(),
+        }
+    }
+
+    pub fn write_gauge(
+        &mut self,
+        metric_name: &str,
+        help: &str,
+        snapshots: &[ExportedSnapshot],
+        get_value: impl Fn(&AggregatorMetricsSnapshot) -> u64,
+    ) {
+        self.write_header(metric_name, help, "gauge");
+        for exported in snapshots {
+            self.write_value(
+                metric_name,
+                &exported.labels,
+                None,
+                get_value(&exported.snapshot),
+            );
+        }
+    }
+
+    pub fn write_counter(
+        &mut self,
+        metric_name: &str,
+        help: &str,
+        snapshots: &[ExportedSnapshot],
+        get_value: impl Fn(&AggregatorMetricsSnapshot) -> u64,
+    ) {
+        self.write_header(metric_name, help, "counter");
+        for exported in snapshots {
+            self.write_value(
+                metric_name,
+                &exported.labels,
+                None,
+                get_value(&exported.snapshot),
+            );
+        }
+    }
+
+    pub fn write_histogram(
+        &mut self,
+        metric_name: &str,
+        help: &str,
+        snapshots: &[ExportedSnapshot],
+        divider: f64,
+        get_histogram: impl Fn(&AggregatorMetricsSnapshot) -> &HistogramSnapshot,
+    ) {
+        self.write_header(metric_name, help, "histogram");
+
+        for exported in snapshots {
+            let histogram = get_histogram(&exported.snapshot);
+            let bucket_name = format!("{}_bucket", metric_name);
+
+            let mut cumulative = 0;
+            for (bound, count) in histogram.bounds.iter().zip(histogram.buckets.iter()) {
+                cumulative += count;
+                let le = format!("{}", *bound as f64 / divider);
+                self.write_value(&bucket_name, &exported.labels, Some(&le), cumulative);
+            }
+
+            self.write_value(
+                &bucket_name,
+                &exported.labels,
+                Some("+Inf"),
+                histogram.count,
+            );
+
+            let _ = writeln!(
+                self.result,
+                "{}_sum{{{}}} {}",
+                metric_name,
+                exported.labels,
+                histogram.sum as f64 / divider
+            );
+
+            self.write_value(
+                &format!("{}_count", metric_name),
+                &exported.labels,
+                None,
+                histogram.count,
+            );
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        self.result
+    }
+
+    fn write_header(&mut self, metric_name: &str, help: &str, metric_type: &str) {
+        let _ = writeln!(self.result, "# HELP {} {}", metric_name, help);
+        let _ = writeln!(self.result, "# TYPE {} {}", metric_name, metric_type);
+    }
+
+    fn write_value(&mut self, metric_name: &str, labels: &str, le: Option<&str>, value: u64) {
+        let _ = match le {
+            Some(le) => writeln!(
+                self.result,
+                "{}{{{},le=\"{}\"}} {}",
+                metric_name, labels, le, value
+            ),
+            None => writeln!(self.result, "{}{{{}}} {}", metric_name, labels, value),
+        };
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
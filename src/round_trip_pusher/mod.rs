@@ -1,5 +1,10 @@
 mod round_trip_callback;
+mod round_trip_owned_callback;
 mod round_trip_pusher;
-mod round_trip_pusher_inner;
+mod round_trip_pusher_builder;
+mod round_trip_pusher_owned_strategy;
+mod round_trip_pusher_strategy;
 pub use round_trip_callback::*;
+pub use round_trip_owned_callback::*;
 pub use round_trip_pusher::*;
+pub use round_trip_pusher_builder::*;
@@ -1,4 +1,20 @@
+use std::future::Future;
+
 #[async_trait::async_trait]
 pub trait RoundTripCallback<TItem> {
     async fn handle(&self, items: &[TItem]);
 }
+
+// The returned future can not borrow the items. Use start_with_lease_fn to keep them
+// across awaits.
+#[async_trait::async_trait]
+impl<TItem, TFn, TFuture> RoundTripCallback<TItem> for TFn
+where
+    TItem: Sync,
+    TFn: Fn(&[TItem]) -> TFuture + Send + Sync,
+    TFuture: Future<Output = ()> + Send + 'static,
+{
+    async fn handle(&self, items: &[TItem]) {
+        self(items).await
+    }
+}
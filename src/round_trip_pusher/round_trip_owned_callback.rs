@@ -0,0 +1,19 @@
+use std::future::Future;
+
+// Receives the batch by value. Returning the items back asks the aggregator to retry them.
+#[async_trait::async_trait]
+pub trait RoundTripOwnedCallback<TItem> {
+    async fn handle(&self, items: Vec<TItem>) -> Result<(), Vec<TItem>>;
+}
+
+#[async_trait::async_trait]
+impl<TItem, TFn, TFuture> RoundTripOwnedCallback<TItem> for TFn
+where
+    TItem: Send + 'static,
+    TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync,
+    TFuture: Future<Output = Result<(), Vec<TItem>>> + Send,
+{
+    async fn handle(&self, items: Vec<TItem>) -> Result<(), Vec<TItem>> {
+        self(items).await
+    }
+}
@@ -1,222 +1,161 @@
-use std::sync::{atomic::AtomicUsize, Arc};
+use std::{future::Future, sync::Arc};
 
 use rust_extensions::{ApplicationStates, Logger};
-use tokio::sync::Mutex;
 
-use crate::RoundTripCallback;
+use crate::{
+    batch_engine::{BatchEngine, ItemsLease},
+    AggregatorEvents, AggregatorHealth, AggregatorMetricsSnapshot, AggregatorRuntime,
+    AggregatorSettings, AggregatorSettingsError, AggregatorStatus, RoundTripCallback,
+    RoundTripOwnedCallback,
+};
 
-use super::round_trip_pusher_inner::RoundTripPusherInner;
+use super::{
+    round_trip_pusher_owned_strategy::RoundTripPusherOwnedStrategy,
+    round_trip_pusher_strategy::RoundTripPusherStrategy, RoundTripPusherBuilder,
+};
 
-pub struct RoundTripPusher<TItem: Send + Sync + 'static> {
-    inner: Arc<(Mutex<RoundTripPusherInner<TItem>>, AtomicUsize)>,
-    sender: tokio::sync::mpsc::UnboundedSender<()>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    name: String,
-    max_amount_per_round_trip: usize,
-    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
-    pub tick_timeout: std::time::Duration,
+pub struct RoundTripPusher<TItem: Send + 'static> {
+    engine: BatchEngine<TItem>,
 }
 
-impl<TItem: Send + Sync + 'static> RoundTripPusher<TItem> {
+impl<TItem: Send + 'static> RoundTripPusher<TItem> {
     pub fn new(
         name: String,
         max_amount_per_round_trip: usize,
         app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
         logger: Arc<dyn Logger + Send + Sync + 'static>,
     ) -> Self {
-        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
-
-        Self {
-            inner: Arc::new((
-                Mutex::new(RoundTripPusherInner::new(receiver)),
-                AtomicUsize::new(0),
-            )),
-            sender,
-            logger,
+        Self::from_settings(
             name,
-            max_amount_per_round_trip,
-            tick_timeout: std::time::Duration::from_secs(10),
+            AggregatorSettings::new(max_amount_per_round_trip),
             app_states,
-        }
+            logger,
+        )
+        .expect("max_amount_per_round_trip must be greater than 0")
+    }
+
+    pub fn builder(
+        name: String,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> RoundTripPusherBuilder<TItem> {
+        RoundTripPusherBuilder::new(name, app_states, logger)
+    }
+
+    pub fn from_settings(
+        name: String,
+        settings: AggregatorSettings,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Result<Self, AggregatorSettingsError> {
+        let engine = BatchEngine::new(name, settings, app_states, logger)?;
+        Ok(Self { engine })
+    }
+
+    pub(crate) fn from_engine(engine: BatchEngine<TItem>) -> Self {
+        Self { engine }
     }
 
     pub fn get_count(&self) -> usize {
-        self.inner.1.load(std::sync::atomic::Ordering::Relaxed)
+        self.engine.get_count()
+    }
+
+    pub fn get_aborted_attempts(&self) -> u64 {
+        self.engine.get_aborted_attempts()
     }
 
-    async fn get_receiver(&self) -> tokio::sync::mpsc::UnboundedReceiver<()> {
-        let mut write_access = self.inner.0.lock().await;
-        let result = write_access.receiver.take();
+    pub fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+        self.engine.get_metrics()
+    }
 
-        if result.is_none() {
-            panic!("You can not start RoundTripPusher twice");
-        }
+    pub fn is_running(&self) -> bool {
+        self.engine.is_running()
+    }
 
-        result.unwrap()
+    pub fn status(&self) -> AggregatorStatus {
+        self.engine.status()
+    }
+
+    pub fn health(&self) -> AggregatorHealth {
+        self.engine.health()
+    }
+
+    pub fn get_settings(&self) -> AggregatorSettings {
+        self.engine.get_settings()
+    }
+
+    pub fn reconfigure(&self, settings: AggregatorSettings) -> Result<(), AggregatorSettingsError> {
+        self.engine.reconfigure(settings)
+    }
+
+    pub fn register_events(&mut self, events: Arc<dyn AggregatorEvents + Send + Sync + 'static>) {
+        self.engine.register_events(events);
+    }
+
+    pub fn set_runtime(&mut self, runtime: Arc<dyn AggregatorRuntime + Send + Sync + 'static>) {
+        self.engine.set_runtime(runtime);
     }
 
     pub async fn start(&self, callback: Arc<dyn RoundTripCallback<TItem> + Send + Sync + 'static>) {
-        let receiver = self.get_receiver().await;
+        self.engine
+            .start(RoundTripPusherStrategy::from_callback(callback));
+    }
 
-        let name = self.name.clone();
-        tokio::spawn(read_loop(
-            name,
-            self.inner.clone(),
-            self.logger.clone(),
-            callback,
-            self.max_amount_per_round_trip,
-            self.tick_timeout,
-            receiver,
-        ));
+    // The closure owns the batch. Returning the items back asks the pusher to retry them.
+    pub async fn start_with_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<(), Vec<TItem>>> + Send + 'static,
+    {
+        self.start_owned(Arc::new(callback)).await;
+    }
+
+    // The lease hands the items back when dropped, so panics and timeouts are retried
+    pub async fn start_with_lease_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = ()> + Send + 'static,
+    {
+        self.engine
+            .start(RoundTripPusherStrategy::from_fn(callback));
+    }
+
+    pub async fn start_owned(
+        &self,
+        callback: Arc<dyn RoundTripOwnedCallback<TItem> + Send + Sync + 'static>,
+    ) {
+        self.engine
+            .start(RoundTripPusherOwnedStrategy::new(callback));
     }
 
     pub async fn publish(&self, item: TItem) {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
-
-        {
-            let mut write_access = self.inner.0.lock().await;
-            write_access.queue.push(item);
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+        self.engine.enqueue(std::iter::once((item, 1))).await;
     }
 
     pub async fn publish_many<TIter: Iterator<Item = TItem>>(&self, items: TIter) {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
-
-        {
-            let mut write_access = self.inner.0.lock().await;
-            write_access.queue.extend(items);
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+        self.engine.enqueue(items.map(|item| (item, 1))).await;
+    }
+
+    // Blocking versions are for producers outside of the async context
+    pub fn publish_blocking(&self, item: TItem) {
+        self.engine.enqueue_blocking(std::iter::once((item, 1)));
+    }
+
+    pub fn publish_many_blocking<TIter: Iterator<Item = TItem>>(&self, items: TIter) {
+        self.engine.enqueue_blocking(items.map(|item| (item, 1)));
+    }
+
+    #[cfg(feature = "with-futures")]
+    pub(crate) fn get_capacity(&self) -> Option<Arc<tokio::sync::Semaphore>> {
+        self.engine.get_capacity()
     }
-}
 
-async fn read_loop<TItem: Send + Sync + 'static>(
-    name: String,
-    inner: Arc<(Mutex<RoundTripPusherInner<TItem>>, AtomicUsize)>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    callback: Arc<dyn RoundTripCallback<TItem> + Send + Sync + 'static>,
-    max_amount_per_round_trip: usize,
-    tick_timeout: std::time::Duration,
-    mut receiver: tokio::sync::mpsc::UnboundedReceiver<()>,
-) {
-    loop {
-        let to_publish = {
-            let mut write_access = inner.0.lock().await;
-
-            if write_access.queue.len() == 0 {
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-                None
-            } else if write_access.queue.len() > max_amount_per_round_trip {
-                let mut to_yield = Vec::with_capacity(max_amount_per_round_trip);
-
-                while to_yield.len() < max_amount_per_round_trip {
-                    to_yield.push(write_access.queue.remove(0));
-                }
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-
-                Some(to_yield)
-            } else {
-                let mut result = Vec::new();
-                std::mem::swap(&mut write_access.queue, &mut result);
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-                Some(result)
-            }
-        };
-
-        if let Some(to_publish) = to_publish {
-            let to_publish = Arc::new(to_publish);
-            let mut attempt_no = 0;
-            loop {
-                let cloned = to_publish.clone();
-                let callback = callback.clone();
-
-                let future = tokio::spawn(async move {
-                    callback.handle(cloned.as_ref()).await;
-                });
-
-                let result = tokio::time::timeout(tick_timeout, future).await;
-
-                attempt_no += 1;
-
-                if attempt_no >= 5 {
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {}. Skipping items", attempt_no),
-                        None,
-                    );
-                    break;
-                }
-
-                if result.is_err() {
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} timeout", attempt_no),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                let result = result.unwrap();
-
-                if let Err(err) = result {
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} panic. Err: {:?}", attempt_no, err),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                break;
-            }
-        } else {
-            receiver.recv().await;
-        }
+    #[cfg(feature = "with-futures")]
+    pub(crate) async fn publish_reserved(
+        &self,
+        item: TItem,
+        permit: Option<tokio::sync::OwnedSemaphorePermit>,
+    ) {
+        self.engine.enqueue_reserved(item, 1, permit).await;
     }
 }
@@ -0,0 +1,11 @@
+use crate::{AggregatorBuilder, AggregatorSettingsError};
+
+use super::RoundTripPusher;
+
+pub type RoundTripPusherBuilder<TItem> = AggregatorBuilder<RoundTripPusher<TItem>>;
+
+impl<TItem: Send + 'static> AggregatorBuilder<RoundTripPusher<TItem>> {
+    pub fn build(self) -> Result<RoundTripPusher<TItem>, AggregatorSettingsError> {
+        Ok(RoundTripPusher::from_engine(self.build_engine()?))
+    }
+}
@@ -0,0 +1,56 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    batch_engine::{BatchCompletion, CompletionReporter, CompletionStrategy},
+    RoundTripOwnedCallback,
+};
+
+pub struct RoundTripPusherOwnedStrategy<TItem: Send + 'static> {
+    callback: Arc<dyn RoundTripOwnedCallback<TItem> + Send + Sync + 'static>,
+}
+
+impl<TItem: Send + 'static> RoundTripPusherOwnedStrategy<TItem> {
+    pub fn new(callback: Arc<dyn RoundTripOwnedCallback<TItem> + Send + Sync + 'static>) -> Self {
+        Self { callback }
+    }
+}
+
+impl<TItem: Send + 'static> CompletionStrategy for RoundTripPusherOwnedStrategy<TItem> {
+    type Request = TItem;
+    type Batch = Option<Vec<TItem>>;
+    type Output = Result<(), Vec<TItem>>;
+
+    fn create_batch(&self, requests: Vec<TItem>) -> Self::Batch {
+        Some(requests)
+    }
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = Self::Output> + Send + 'static>> {
+        let items = batch.take().unwrap();
+        let callback = self.callback.clone();
+
+        Box::pin(async move { callback.handle(items).await })
+    }
+
+    fn complete(
+        &self,
+        _batch: Self::Batch,
+        output: Self::Output,
+        _reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch> {
+        match output {
+            Ok(_) => BatchCompletion::Delivered,
+            Err(items) => {
+                BatchCompletion::Retry(Some(items), "Callback returned items back".to_string())
+            }
+        }
+    }
+
+    fn can_retry(&self, batch: &Self::Batch) -> bool {
+        batch.is_some()
+    }
+
+    fn drop_batch(&self, _batch: Self::Batch, _reason: &str, _reporter: &CompletionReporter) {}
+}
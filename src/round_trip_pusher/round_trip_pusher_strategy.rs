@@ -0,0 +1,79 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    batch_engine::{
+        BatchCompletion, CompletionReporter, CompletionStrategy, ItemsLease, ItemsSlot,
+        LeaseCallback,
+    },
+    RoundTripCallback,
+};
+
+pub struct RoundTripPusherStrategy<TItem: Send + 'static> {
+    callback: LeaseCallback<TItem, ()>,
+}
+
+impl<TItem: Send + 'static> RoundTripPusherStrategy<TItem> {
+    pub fn from_callback(
+        callback: Arc<dyn RoundTripCallback<TItem> + Send + Sync + 'static>,
+    ) -> Self {
+        Self::from_fn(move |items: ItemsLease<TItem>| {
+            let callback = callback.clone();
+            async move { callback.handle(&items).await }
+        })
+    }
+
+    pub fn from_fn<TFn, TFuture>(callback: TFn) -> Self
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            callback: Arc::new(move |items| Box::pin(callback(items))),
+        }
+    }
+}
+
+impl<TItem: Send + 'static> CompletionStrategy for RoundTripPusherStrategy<TItem> {
+    type Request = TItem;
+    type Batch = ItemsSlot<TItem>;
+    type Output = ();
+
+    fn create_batch(&self, requests: Vec<TItem>) -> Self::Batch {
+        ItemsSlot::new(requests)
+    }
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let items = batch.clone();
+        let callback = self.callback.clone();
+
+        Box::pin(async move {
+            let lease = items.lease(
+                #[cfg(feature = "with-telemetry")]
+                Arc::new(my_telemetry::MyTelemetryCompiler::new().compile()),
+            );
+            callback(lease).await;
+        })
+    }
+
+    fn complete(
+        &self,
+        _batch: Self::Batch,
+        _output: (),
+        _reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch> {
+        BatchCompletion::Delivered
+    }
+
+    fn can_retry(&self, batch: &Self::Batch) -> bool {
+        !batch.is_lost()
+    }
+
+    fn items_returned(&self, batch: &Self::Batch) -> bool {
+        !batch.is_empty()
+    }
+
+    fn drop_batch(&self, _batch: Self::Batch, _reason: &str, _reporter: &CompletionReporter) {}
+}
@@ -0,0 +1,52 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use super::{
+    ticket_slot::{TicketOutcome, TicketSlot},
+    BatchTicketStatus,
+};
+
+// Completion handle of a submitted request. It can be awaited, dropped,
+// or checked with status() without blocking.
+pub struct BatchTicket<TError: Send + Sync + 'static> {
+    slot: Arc<TicketSlot<TError>>,
+}
+
+impl<TError: Send + Sync + 'static> BatchTicket<TError> {
+    pub(crate) fn new(slot: Arc<TicketSlot<TError>>) -> Self {
+        Self { slot }
+    }
+
+    pub fn status(&self) -> BatchTicketStatus {
+        self.slot
+            .read(None, |outcome| match outcome {
+                TicketOutcome::Delivered => BatchTicketStatus::Delivered,
+                TicketOutcome::Failed(_) => BatchTicketStatus::Failed,
+                TicketOutcome::Dropped(message) => BatchTicketStatus::Dropped(message.clone()),
+            })
+            .unwrap_or(BatchTicketStatus::Pending)
+    }
+}
+
+impl<TError: Send + Sync + 'static> Future for BatchTicket<TError> {
+    type Output = Result<(), Arc<TError>>;
+
+    // A dropped batch panics the awaiting caller, the same way execute_request does
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = self.slot.read(Some(cx.waker()), |outcome| match outcome {
+            TicketOutcome::Delivered => Ok(Ok(())),
+            TicketOutcome::Failed(err) => Ok(Err(err.clone())),
+            TicketOutcome::Dropped(message) => Err(message.clone()),
+        });
+
+        match result {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(message)) => panic!("{}", message),
+            None => Poll::Pending,
+        }
+    }
+}
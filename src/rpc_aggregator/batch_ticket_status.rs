@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchTicketStatus {
+    Pending,
+    Delivered,
+    Failed,
+    Dropped(String),
+}
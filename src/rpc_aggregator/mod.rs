@@ -1,7 +1,20 @@
+mod batch_ticket;
+mod batch_ticket_status;
+mod owned_callback_error;
 mod rcp_aggregator;
-mod rcp_aggregator_inner;
+mod request_completion;
+mod rpc_aggregator_builder;
 mod rpc_aggregator_callback;
+mod rpc_aggregator_owned_callback;
+mod rpc_aggregator_owned_strategy;
+mod rpc_aggregator_strategy;
 mod rpc_request_data;
+mod ticket_slot;
 
+pub use batch_ticket::*;
+pub use batch_ticket_status::*;
+pub use owned_callback_error::*;
 pub use rcp_aggregator::*;
+pub use rpc_aggregator_builder::*;
 pub use rpc_aggregator_callback::*;
+pub use rpc_aggregator_owned_callback::*;
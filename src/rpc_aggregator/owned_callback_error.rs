@@ -0,0 +1,5 @@
+pub enum OwnedCallbackError<TItem, TError> {
+    // Items are handed back to the aggregator to be retried
+    Retry(Vec<TItem>),
+    Failed(TError),
+}
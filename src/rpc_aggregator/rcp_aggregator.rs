@@ -1,82 +1,137 @@
-use std::{
-    collections::BTreeMap,
-    sync::{atomic::AtomicUsize, Arc},
-};
+use std::{collections::BTreeMap, future::Future, sync::Arc};
 
-use rust_extensions::{ApplicationStates, Logger, TaskCompletion};
-use tokio::sync::Mutex;
+use rust_extensions::{ApplicationStates, Logger};
 
-use crate::RpcAggregatorCallback;
+use crate::{
+    batch_engine::{BatchEngine, ItemsLease},
+    task_completion::{TaskCompletion, TaskCompletionAwaiter},
+    AggregatorEvents, AggregatorHealth, AggregatorMetricsSnapshot, AggregatorRuntime,
+    AggregatorSettings, AggregatorSettingsError, AggregatorStatus, BatchTicket, OwnedCallbackError,
+    RpcAggregatorCallback, RpcAggregatorOwnedCallback,
+};
 
 use super::{
-    rcp_aggregator_inner::RpcAggregatorInner,
-    rpc_request_data::{RcpRequestData, Request},
+    request_completion::RequestCompletion,
+    rpc_aggregator_owned_strategy::RpcAggregatorOwnedStrategy,
+    rpc_aggregator_strategy::RpcAggregatorStrategy,
+    rpc_request_data::Request,
+    ticket_slot::{TicketCompletion, TicketSlot},
+    RpcAggregatorBuilder,
 };
 
-pub struct RpcAggregator<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> {
-    inner: Arc<(Mutex<RpcAggregatorInner<TItem, TError>>, AtomicUsize)>,
-    sender: tokio::sync::mpsc::UnboundedSender<()>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    name: String,
-    max_amount_per_round_trip: usize,
-    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
-    pub tick_timeout: std::time::Duration,
+pub struct RpcAggregator<TItem: Send + 'static, TError: Send + Sync + 'static> {
+    engine: BatchEngine<Request<TItem, TError>>,
 }
 
-impl<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> RpcAggregator<TItem, TError> {
+impl<TItem: Send + 'static, TError: Send + Sync + 'static> RpcAggregator<TItem, TError> {
     pub fn new(
         name: String,
         max_amount_per_round_trip: usize,
         app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
         logger: Arc<dyn Logger + Send + Sync + 'static>,
     ) -> Self {
-        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
-
-        Self {
-            inner: Arc::new((
-                Mutex::new(RpcAggregatorInner::new(receiver)),
-                AtomicUsize::new(0),
-            )),
-            sender,
-            logger,
+        Self::from_settings(
             name,
-            max_amount_per_round_trip,
-            tick_timeout: std::time::Duration::from_secs(10),
+            AggregatorSettings::new(max_amount_per_round_trip),
             app_states,
-        }
+            logger,
+        )
+        .expect("max_amount_per_round_trip must be greater than 0")
+    }
+
+    pub fn builder(
+        name: String,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> RpcAggregatorBuilder<TItem, TError> {
+        RpcAggregatorBuilder::new(name, app_states, logger)
+    }
+
+    pub fn from_settings(
+        name: String,
+        settings: AggregatorSettings,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Result<Self, AggregatorSettingsError> {
+        let engine = BatchEngine::new(name, settings, app_states, logger)?;
+        Ok(Self { engine })
+    }
+
+    pub(crate) fn from_engine(engine: BatchEngine<Request<TItem, TError>>) -> Self {
+        Self { engine }
     }
 
     pub fn get_count(&self) -> usize {
-        self.inner.1.load(std::sync::atomic::Ordering::Relaxed)
+        self.engine.get_count()
+    }
+
+    pub fn get_aborted_attempts(&self) -> u64 {
+        self.engine.get_aborted_attempts()
     }
 
-    async fn get_receiver(&self) -> tokio::sync::mpsc::UnboundedReceiver<()> {
-        let mut write_access = self.inner.0.lock().await;
-        let result = write_access.receiver.take();
+    pub fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+        self.engine.get_metrics()
+    }
 
-        if result.is_none() {
-            panic!("You can not start RoundTripPusher twice");
-        }
+    pub fn is_running(&self) -> bool {
+        self.engine.is_running()
+    }
+
+    pub fn status(&self) -> AggregatorStatus {
+        self.engine.status()
+    }
 
-        result.unwrap()
+    pub fn health(&self) -> AggregatorHealth {
+        self.engine.health()
+    }
+
+    pub fn get_settings(&self) -> AggregatorSettings {
+        self.engine.get_settings()
+    }
+
+    pub fn reconfigure(&self, settings: AggregatorSettings) -> Result<(), AggregatorSettingsError> {
+        self.engine.reconfigure(settings)
+    }
+
+    pub fn register_events(&mut self, events: Arc<dyn AggregatorEvents + Send + Sync + 'static>) {
+        self.engine.register_events(events);
+    }
+
+    pub fn set_runtime(&mut self, runtime: Arc<dyn AggregatorRuntime + Send + Sync + 'static>) {
+        self.engine.set_runtime(runtime);
     }
 
     pub async fn start(
         &self,
         callback: Arc<dyn RpcAggregatorCallback<TItem, TError> + Send + Sync + 'static>,
     ) {
-        let receiver = self.get_receiver().await;
+        self.engine
+            .start(RpcAggregatorStrategy::from_callback(callback));
+    }
 
-        let name = self.name.clone();
-        tokio::spawn(read_loop(
-            name,
-            self.inner.clone(),
-            self.logger.clone(),
-            callback,
-            self.max_amount_per_round_trip,
-            self.tick_timeout,
-            receiver,
-        ));
+    // The closure owns the batch. OwnedCallbackError::Retry hands the items back.
+    pub async fn start_with_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<(), OwnedCallbackError<TItem, TError>>> + Send + 'static,
+    {
+        self.start_owned(Arc::new(callback)).await;
+    }
+
+    // The lease hands the items back when dropped, so failed attempts are retried
+    pub async fn start_with_lease_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<(), TError>> + Send + 'static,
+    {
+        self.engine.start(RpcAggregatorStrategy::from_fn(callback));
+    }
+
+    pub async fn start_owned(
+        &self,
+        callback: Arc<dyn RpcAggregatorOwnedCallback<TItem, TError> + Send + Sync + 'static>,
+    ) {
+        self.engine.start(RpcAggregatorOwnedStrategy::new(callback));
     }
 
     pub async fn execute_request(
@@ -84,40 +139,34 @@ impl<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> RpcAggregator<
         data: TItem,
         #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
     ) -> Result<(), Arc<TError>> {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
-
-        let mut event = Request {
-            request_data: vec![data],
-            completion: TaskCompletion::new(),
+        self.submit(
+            data,
             #[cfg(feature = "with-telemetry")]
             my_telemetry,
-        };
+        )
+        .await
+        .await
+    }
 
-        let task_await = event.completion.get_awaiter();
+    // Returns as soon as the request is queued. With queue_capacity set it waits
+    // for a free slot first, the same way publishing does.
+    pub async fn submit(
+        &self,
+        data: TItem,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> BatchTicket<TError> {
+        let slot = Arc::new(TicketSlot::new());
 
-        {
-            let mut write_access = self.inner.0.lock().await;
+        let request = self.create_request(
+            data,
+            RequestCompletion::Ticket(TicketCompletion::new(slot.clone())),
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+        );
 
-            write_access.queue.push(event);
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+        self.engine.enqueue(std::iter::once((request, 1))).await;
 
-        task_await.get_result().await
+        BatchTicket::new(slot)
     }
 
     pub async fn execute_multi_requests(
@@ -125,217 +174,89 @@ impl<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> RpcAggregator<
         data: Vec<TItem>,
         #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
     ) -> Result<(), BTreeMap<usize, Arc<TError>>> {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
-
-        let mut awaiters = Vec::with_capacity(data.len());
-
-        {
-            let mut write_access = self.inner.0.lock().await;
-
-            let mut event = Request {
-                request_data: data,
-                completion: TaskCompletion::new(),
+        let awaiters = self
+            .execute(
+                data,
                 #[cfg(feature = "with-telemetry")]
                 my_telemetry,
-            };
-
-            let task_await = event.completion.get_awaiter();
-            write_access.queue.push(event);
-
-            awaiters.push(task_await);
-
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+            )
+            .await;
 
-        let mut errs: Option<BTreeMap<usize, Arc<TError>>> = None;
+        let mut errs = BTreeMap::new();
 
-        let mut i = 0;
-        for awaiter in awaiters {
+        for (index, awaiter) in awaiters.into_iter().enumerate() {
             if let Err(err) = awaiter.get_result().await {
-                if errs.is_none() {
-                    errs = Some(BTreeMap::new());
-                }
-
-                errs.as_mut().unwrap().insert(i, err);
+                errs.insert(index, err);
             }
-
-            i += 1
         }
 
-        if let Some(errs) = errs {
-            Err(errs)
-        } else {
+        if errs.is_empty() {
             Ok(())
+        } else {
+            Err(errs)
         }
     }
-}
 
-async fn read_loop<TItem: Send + Sync + 'static, TError: Send + Sync + 'static>(
-    name: String,
-    inner: Arc<(Mutex<RpcAggregatorInner<TItem, TError>>, AtomicUsize)>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    callback: Arc<dyn RpcAggregatorCallback<TItem, TError> + Send + Sync + 'static>,
-    max_amount_per_round_trip: usize,
-    tick_timeout: std::time::Duration,
-    mut receiver: tokio::sync::mpsc::UnboundedReceiver<()>,
-) {
-    loop {
-        let to_publish = {
-            let mut write_access = inner.0.lock().await;
-
-            if write_access.queue.len() == 0 {
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-                None
-            } else if write_access.queue.len() > max_amount_per_round_trip {
-                let mut to_yield = Vec::with_capacity(max_amount_per_round_trip);
-                #[cfg(feature = "with-telemetry")]
-                let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
-                while to_yield.len() < max_amount_per_round_trip {
-                    let item = write_access.queue.remove(0);
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.add(&item.my_telemetry);
-                    to_yield.push(item);
-                }
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-
-                Some(RcpRequestData::new(
-                    to_yield,
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.compile(),
-                ))
-            } else {
-                let mut result = Vec::new();
-                std::mem::swap(&mut write_access.queue, &mut result);
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-                #[cfg(feature = "with-telemetry")]
-                let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
-                #[cfg(feature = "with-telemetry")]
-                for item in &result {
-                    ctx_compiler.add(&item.my_telemetry);
-                }
-
-                Some(RcpRequestData::new(
-                    result,
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.compile(),
-                ))
-            }
-        };
+    pub fn execute_request_blocking(
+        &self,
+        data: TItem,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Result<(), Arc<TError>> {
+        let mut completion = TaskCompletion::new();
+        let awaiter = completion.get_awaiter();
 
-        if let Some(mut to_publish) = to_publish {
-            let data_to_callback = to_publish.get_data_to_callback();
+        let request = self.create_request(
+            data,
+            RequestCompletion::Awaiter(completion),
             #[cfg(feature = "with-telemetry")]
-            let my_telemetry = to_publish.get_telemetry();
+            my_telemetry,
+        );
+
+        self.engine.enqueue_blocking(std::iter::once((request, 1)));
+
+        crate::block_on::block_on(awaiter.get_result())
+    }
 
-            let mut attempt_no = 0;
-            loop {
-                let cloned = data_to_callback.clone();
-                let callback = callback.clone();
+    async fn execute(
+        &self,
+        data: Vec<TItem>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Vec<TaskCompletionAwaiter<(), Arc<TError>>> {
+        let mut awaiters = Vec::with_capacity(data.len());
+        let mut requests = Vec::with_capacity(data.len());
+
+        for item in data {
+            let mut completion = TaskCompletion::new();
+            awaiters.push(completion.get_awaiter());
+            requests.push(self.create_request(
+                item,
+                RequestCompletion::Awaiter(completion),
                 #[cfg(feature = "with-telemetry")]
-                let my_telemetry_cloned = my_telemetry.clone();
-                let future = tokio::spawn(async move {
-                    callback
-                        .handle(
-                            cloned.as_ref(),
-                            #[cfg(feature = "with-telemetry")]
-                            my_telemetry_cloned.as_ref(),
-                        )
-                        .await
-                });
-
-                let result = tokio::time::timeout(tick_timeout, future).await;
-
-                attempt_no += 1;
-
-                if result.is_err() {
-                    if attempt_no >= 5 {
-                        logger.write_fatal_error(
-                            format!("round trip pusher {}", name),
-                            format!("Attempt {}. Skipping items", attempt_no),
-                            None,
-                        );
-
-                        to_publish.set_panic("Timeout");
-
-                        break;
-                    }
-
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} timeout", attempt_no),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                let result = result.unwrap();
-
-                if let Err(err) = &result {
-                    if attempt_no >= 5 {
-                        logger.write_fatal_error(
-                            format!("round trip pusher {}", name),
-                            format!("Attempt {}. Skipping items", attempt_no),
-                            None,
-                        );
-
-                        to_publish.set_panic(format!("{}", err).as_str());
-
-                        break;
-                    }
-
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} panic. Err: {:?}", attempt_no, err),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                match result.unwrap() {
-                    Ok(_) => {
-                        if let Err(message) = to_publish.set_result() {
-                            to_publish.set_panic(message.as_str());
-                        }
-                        break;
-                    }
-                    Err(err) => {
-                        to_publish.set_error(err);
-                        break;
-                    }
-                }
-            }
-        } else {
-            receiver.recv().await;
+                my_telemetry.clone(),
+            ));
+        }
+
+        self.engine
+            .enqueue(requests.into_iter().map(|request| (request, 1)))
+            .await;
+
+        awaiters
+    }
+
+    // Every item is queued as a separate request, so it gets its own outcome
+    // even if the items end up in different batches. Requests made before start wait
+    // in the queue until the aggregator is started.
+    fn create_request(
+        &self,
+        data: TItem,
+        completion: RequestCompletion<TError>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Request<TItem, TError> {
+        Request {
+            request_data: data,
+            completion,
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
         }
     }
 }
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::{batch_engine::CompletionReporter, task_completion::TaskCompletion};
+
+use super::ticket_slot::{TicketCompletion, TicketOutcome};
+
+pub enum RequestCompletion<TError: Send + Sync + 'static> {
+    Awaiter(TaskCompletion<(), Arc<TError>>),
+    Ticket(TicketCompletion<TError>),
+}
+
+impl<TError: Send + Sync + 'static> RequestCompletion<TError> {
+    pub fn set_ok(&mut self, reporter: &CompletionReporter) {
+        match self {
+            Self::Awaiter(completion) => {
+                if let Err(err) = completion.try_set_ok(()) {
+                    reporter.report_unset("Ok", err);
+                }
+            }
+            Self::Ticket(completion) => completion.set(TicketOutcome::Delivered),
+        }
+    }
+
+    pub fn set_panic(&mut self, message: &str, reporter: &CompletionReporter) {
+        match self {
+            Self::Awaiter(completion) => {
+                if let Err(err) = completion.try_set_panic(message.to_string()) {
+                    reporter.report_unset("panic", err);
+                }
+            }
+            Self::Ticket(completion) => completion.set(TicketOutcome::Dropped(message.to_string())),
+        }
+    }
+
+    pub fn set_error(&mut self, err: Arc<TError>, reporter: &CompletionReporter) {
+        match self {
+            Self::Awaiter(completion) => {
+                if let Err(err) = completion.try_set_error(err) {
+                    reporter.report_unset("error", err);
+                }
+            }
+            Self::Ticket(completion) => completion.set(TicketOutcome::Failed(err)),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::{AggregatorBuilder, AggregatorSettingsError};
+
+use super::RpcAggregator;
+
+pub type RpcAggregatorBuilder<TItem, TError> = AggregatorBuilder<RpcAggregator<TItem, TError>>;
+
+impl<TItem: Send + 'static, TError: Send + Sync + 'static>
+    AggregatorBuilder<RpcAggregator<TItem, TError>>
+{
+    pub fn build(self) -> Result<RpcAggregator<TItem, TError>, AggregatorSettingsError> {
+        Ok(RpcAggregator::from_engine(self.build_engine()?))
+    }
+}
@@ -1,3 +1,5 @@
+use std::future::Future;
+
 #[async_trait::async_trait]
 pub trait RpcAggregatorCallback<TItem, TError> {
     async fn handle(
@@ -6,3 +8,20 @@ pub trait RpcAggregatorCallback<TItem, TError> {
         #[cfg(feature = "with-telemetry")] my_telemetry: &my_telemetry::MyTelemetryContext,
     ) -> Result<(), TError>;
 }
+
+// Closures do not get the telemetry context. ItemsLease::get_telemetry has it.
+#[async_trait::async_trait]
+impl<TItem, TError, TFn, TFuture> RpcAggregatorCallback<TItem, TError> for TFn
+where
+    TItem: Sync,
+    TFn: Fn(&[TItem]) -> TFuture + Send + Sync,
+    TFuture: Future<Output = Result<(), TError>> + Send + 'static,
+{
+    async fn handle(
+        &self,
+        items: &[TItem],
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<(), TError> {
+        self(items).await
+    }
+}
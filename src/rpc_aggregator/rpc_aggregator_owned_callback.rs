@@ -0,0 +1,28 @@
+use std::future::Future;
+
+use crate::OwnedCallbackError;
+
+#[async_trait::async_trait]
+pub trait RpcAggregatorOwnedCallback<TItem, TError> {
+    async fn handle(
+        &self,
+        items: Vec<TItem>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<(), OwnedCallbackError<TItem, TError>>;
+}
+
+#[async_trait::async_trait]
+impl<TItem, TError, TFn, TFuture> RpcAggregatorOwnedCallback<TItem, TError> for TFn
+where
+    TItem: Send + 'static,
+    TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync,
+    TFuture: Future<Output = Result<(), OwnedCallbackError<TItem, TError>>> + Send,
+{
+    async fn handle(
+        &self,
+        items: Vec<TItem>,
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<(), OwnedCallbackError<TItem, TError>> {
+        self(items).await
+    }
+}
@@ -0,0 +1,85 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    batch_engine::{BatchCompletion, CompletionReporter, CompletionStrategy},
+    OwnedCallbackError, RpcAggregatorOwnedCallback,
+};
+
+use super::rpc_request_data::{RcpRequestData, Request};
+
+pub struct RpcAggregatorOwnedStrategy<TItem: Send + 'static, TError: Send + Sync + 'static> {
+    callback: Arc<dyn RpcAggregatorOwnedCallback<TItem, TError> + Send + Sync + 'static>,
+}
+
+impl<TItem: Send + 'static, TError: Send + Sync + 'static>
+    RpcAggregatorOwnedStrategy<TItem, TError>
+{
+    pub fn new(
+        callback: Arc<dyn RpcAggregatorOwnedCallback<TItem, TError> + Send + Sync + 'static>,
+    ) -> Self {
+        Self { callback }
+    }
+}
+
+impl<TItem: Send + 'static, TError: Send + Sync + 'static> CompletionStrategy
+    for RpcAggregatorOwnedStrategy<TItem, TError>
+{
+    type Request = Request<TItem, TError>;
+    type Batch = (RcpRequestData<TError>, Option<Vec<TItem>>);
+    type Output = Result<(), OwnedCallbackError<TItem, TError>>;
+
+    fn create_batch(&self, requests: Vec<Self::Request>) -> Self::Batch {
+        let (request_data, items) = RcpRequestData::new(requests);
+        (request_data, Some(items))
+    }
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = Self::Output> + Send + 'static>> {
+        let items = batch.1.take().unwrap();
+        #[cfg(feature = "with-telemetry")]
+        let my_telemetry = batch.0.get_telemetry();
+        let callback = self.callback.clone();
+
+        Box::pin(async move {
+            callback
+                .handle(
+                    items,
+                    #[cfg(feature = "with-telemetry")]
+                    my_telemetry.as_ref(),
+                )
+                .await
+        })
+    }
+
+    fn complete(
+        &self,
+        (batch, _): Self::Batch,
+        output: Self::Output,
+        reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch> {
+        match output {
+            Ok(_) => {
+                batch.set_result(reporter);
+                BatchCompletion::Delivered
+            }
+            Err(OwnedCallbackError::Retry(items)) => BatchCompletion::Retry(
+                (batch, Some(items)),
+                "Callback returned items back".to_string(),
+            ),
+            Err(OwnedCallbackError::Failed(err)) => {
+                batch.set_error(err, reporter);
+                BatchCompletion::Failed("Callback returned an error".to_string())
+            }
+        }
+    }
+
+    fn can_retry(&self, batch: &Self::Batch) -> bool {
+        batch.1.is_some()
+    }
+
+    fn drop_batch(&self, (batch, _): Self::Batch, reason: &str, reporter: &CompletionReporter) {
+        batch.set_panic(reason, reporter);
+    }
+}
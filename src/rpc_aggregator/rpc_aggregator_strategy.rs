@@ -0,0 +1,105 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    batch_engine::{
+        BatchCompletion, CompletionReporter, CompletionStrategy, ItemsLease, ItemsSlot,
+        LeaseCallback,
+    },
+    RpcAggregatorCallback,
+};
+
+use super::rpc_request_data::{RcpRequestData, Request};
+
+pub struct RpcAggregatorStrategy<TItem: Send + 'static, TError: Send + Sync + 'static> {
+    callback: LeaseCallback<TItem, Result<(), TError>>,
+}
+
+impl<TItem: Send + 'static, TError: Send + Sync + 'static> RpcAggregatorStrategy<TItem, TError> {
+    pub fn from_callback(
+        callback: Arc<dyn RpcAggregatorCallback<TItem, TError> + Send + Sync + 'static>,
+    ) -> Self {
+        Self::from_fn(move |items: ItemsLease<TItem>| {
+            let callback = callback.clone();
+            async move {
+                callback
+                    .handle(
+                        &items,
+                        #[cfg(feature = "with-telemetry")]
+                        items.get_telemetry(),
+                    )
+                    .await
+            }
+        })
+    }
+
+    pub fn from_fn<TFn, TFuture>(callback: TFn) -> Self
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<(), TError>> + Send + 'static,
+    {
+        Self {
+            callback: Arc::new(move |items| Box::pin(callback(items))),
+        }
+    }
+}
+
+impl<TItem: Send + 'static, TError: Send + Sync + 'static> CompletionStrategy
+    for RpcAggregatorStrategy<TItem, TError>
+{
+    type Request = Request<TItem, TError>;
+    type Batch = (RcpRequestData<TError>, ItemsSlot<TItem>);
+    type Output = Result<(), TError>;
+
+    fn create_batch(&self, requests: Vec<Self::Request>) -> Self::Batch {
+        let (request_data, items) = RcpRequestData::new(requests);
+        (request_data, ItemsSlot::new(items))
+    }
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = Self::Output> + Send + 'static>> {
+        let data = batch.1.clone();
+        #[cfg(feature = "with-telemetry")]
+        let my_telemetry = batch.0.get_telemetry();
+        let callback = self.callback.clone();
+
+        Box::pin(async move {
+            let lease = data.lease(
+                #[cfg(feature = "with-telemetry")]
+                my_telemetry,
+            );
+            callback(lease).await
+        })
+    }
+
+    fn complete(
+        &self,
+        (batch, _): Self::Batch,
+        output: Self::Output,
+        reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch> {
+        match output {
+            Ok(_) => {
+                batch.set_result(reporter);
+                BatchCompletion::Delivered
+            }
+            Err(err) => {
+                batch.set_error(err, reporter);
+                BatchCompletion::Failed("Callback returned an error".to_string())
+            }
+        }
+    }
+
+    fn can_retry(&self, batch: &Self::Batch) -> bool {
+        !batch.1.is_lost()
+    }
+
+    fn items_returned(&self, batch: &Self::Batch) -> bool {
+        !batch.1.is_empty()
+    }
+
+    fn drop_batch(&self, (batch, _): Self::Batch, reason: &str, reporter: &CompletionReporter) {
+        batch.set_panic(reason, reporter);
+    }
+}
@@ -1,79 +1,67 @@
 use std::sync::Arc;
 
-use rust_extensions::TaskCompletion;
+use crate::batch_engine::CompletionReporter;
 
-pub struct Request<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> {
-    pub request_data: Vec<TItem>,
-    pub completion: TaskCompletion<(), Arc<TError>>,
+use super::request_completion::RequestCompletion;
+
+pub struct Request<TItem: Send + 'static, TError: Send + Sync + 'static> {
+    pub request_data: TItem,
+    pub completion: RequestCompletion<TError>,
 
     #[cfg(feature = "with-telemetry")]
     pub my_telemetry: my_telemetry::MyTelemetryContext,
 }
 
-pub struct RcpRequestData<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> {
-    data: Option<Vec<TItem>>,
-    completions: Vec<TaskCompletion<(), Arc<TError>>>,
+pub struct RcpRequestData<TError: Send + Sync + 'static> {
+    completions: Vec<RequestCompletion<TError>>,
     #[cfg(feature = "with-telemetry")]
-    my_telemetry: Option<my_telemetry::MyTelemetryContext>,
+    my_telemetry: Arc<my_telemetry::MyTelemetryContext>,
 }
 
-impl<TItem: Send + Sync + 'static, TError: Send + Sync + 'static> RcpRequestData<TItem, TError> {
-    pub fn new(
-        requests: Vec<Request<TItem, TError>>,
-        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
-    ) -> Self {
-        let mut data = Vec::new();
+impl<TError: Send + Sync + 'static> RcpRequestData<TError> {
+    pub fn new<TItem: Send + 'static>(requests: Vec<Request<TItem, TError>>) -> (Self, Vec<TItem>) {
+        let mut data = Vec::with_capacity(requests.len());
         let mut completions = Vec::with_capacity(requests.len());
+        #[cfg(feature = "with-telemetry")]
+        let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
 
         for request in requests {
-            data.extend(request.request_data);
+            #[cfg(feature = "with-telemetry")]
+            ctx_compiler.add(&request.my_telemetry);
+            data.push(request.request_data);
             completions.push(request.completion);
         }
 
-        Self {
-            data: Some(data),
+        let result = Self {
             completions,
             #[cfg(feature = "with-telemetry")]
-            my_telemetry: Some(my_telemetry),
-        }
-    }
+            my_telemetry: Arc::new(ctx_compiler.compile()),
+        };
 
-    pub fn get_data_to_callback(&mut self) -> Arc<Vec<TItem>> {
-        let mut new_result = None;
-        std::mem::swap(&mut new_result, &mut self.data);
-        Arc::new(new_result.unwrap())
+        (result, data)
     }
 
     #[cfg(feature = "with-telemetry")]
-    pub fn get_telemetry(&mut self) -> Arc<my_telemetry::MyTelemetryContext> {
-        let mut new_result = None;
-        std::mem::swap(&mut new_result, &mut self.my_telemetry);
-        Arc::new(new_result.unwrap())
+    pub fn get_telemetry(&self) -> Arc<my_telemetry::MyTelemetryContext> {
+        self.my_telemetry.clone()
     }
 
-    pub fn set_result(&mut self) -> Result<(), String> {
+    pub fn set_result(mut self, reporter: &CompletionReporter) {
         for completion in &mut self.completions {
-            if let Err(err) = completion.try_set_ok(()) {
-                println!("Can not set Ok result to the task completion. {:?}", err);
-            }
+            completion.set_ok(reporter);
         }
-        Ok(())
     }
 
-    pub fn set_panic(mut self, message: &str) {
+    pub fn set_panic(mut self, message: &str, reporter: &CompletionReporter) {
         for completion in &mut self.completions {
-            if let Err(err) = completion.try_set_panic(message.to_string()) {
-                println!("Can not set panic result to the task completion. {:?}", err);
-            }
+            completion.set_panic(message, reporter);
         }
     }
 
-    pub fn set_error(mut self, err: TError) {
+    pub fn set_error(mut self, err: TError, reporter: &CompletionReporter) {
         let err = Arc::new(err);
         for completion in &mut self.completions {
-            if let Err(err) = completion.try_set_error(err.clone()) {
-                println!("set_error: {:?}", err);
-            }
+            completion.set_error(err.clone(), reporter);
         }
     }
 }
@@ -0,0 +1,87 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::Waker,
+};
+
+pub enum TicketOutcome<TError: Send + Sync + 'static> {
+    Delivered,
+    Failed(Arc<TError>),
+    Dropped(String),
+}
+
+// Outcome of a submitted request. It is filled once by the read loop, and both
+// BatchTicket::status and BatchTicket::poll read it.
+pub struct TicketSlot<TError: Send + Sync + 'static> {
+    state: Mutex<TicketSlotState<TError>>,
+}
+
+struct TicketSlotState<TError: Send + Sync + 'static> {
+    outcome: Option<TicketOutcome<TError>>,
+    waker: Option<Waker>,
+}
+
+impl<TError: Send + Sync + 'static> TicketSlot<TError> {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(TicketSlotState {
+                outcome: None,
+                waker: None,
+            }),
+        }
+    }
+
+    // The first outcome wins
+    pub fn set(&self, outcome: TicketOutcome<TError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            if state.outcome.is_some() {
+                return;
+            }
+            state.outcome = Some(outcome);
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub fn read<TResult>(
+        &self,
+        waker: Option<&Waker>,
+        read: impl FnOnce(&TicketOutcome<TError>) -> TResult,
+    ) -> Option<TResult> {
+        let mut state = self.state.lock().unwrap();
+        match &state.outcome {
+            Some(outcome) => Some(read(outcome)),
+            None => {
+                if let Some(waker) = waker {
+                    state.waker = Some(waker.clone());
+                }
+                None
+            }
+        }
+    }
+}
+
+// Held by the queued request. A request dropped without an outcome marks the ticket as dropped.
+pub struct TicketCompletion<TError: Send + Sync + 'static> {
+    slot: Arc<TicketSlot<TError>>,
+}
+
+impl<TError: Send + Sync + 'static> TicketCompletion<TError> {
+    pub fn new(slot: Arc<TicketSlot<TError>>) -> Self {
+        Self { slot }
+    }
+
+    pub fn set(&self, outcome: TicketOutcome<TError>) {
+        self.slot.set(outcome);
+    }
+}
+
+impl<TError: Send + Sync + 'static> Drop for TicketCompletion<TError> {
+    fn drop(&mut self) {
+        self.slot
+            .set(TicketOutcome::Dropped("Batch is dropped".to_string()));
+    }
+}
@@ -1,7 +1,12 @@
-mod rcp_aggregator_inner;
 mod rcp_aggregator_with_result;
+mod rpc_aggregator_with_result_builder;
 mod rpc_aggregator_with_result_callback;
+mod rpc_aggregator_with_result_owned_callback;
+mod rpc_aggregator_with_result_owned_strategy;
+mod rpc_aggregator_with_result_strategy;
 mod rpc_request_data;
 
 pub use rcp_aggregator_with_result::*;
+pub use rpc_aggregator_with_result_builder::*;
 pub use rpc_aggregator_with_result_callback::*;
+pub use rpc_aggregator_with_result_owned_callback::*;
@@ -1,37 +1,30 @@
-use std::sync::{atomic::AtomicUsize, Arc};
-
-use tokio::sync::Mutex;
-
-use crate::RpcAggregatorWithResultCallback;
-use rust_extensions::{ApplicationStates, Logger, TaskCompletion};
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    batch_engine::{BatchEngine, ItemsLease},
+    task_completion::TaskCompletion,
+    AggregatorEvents, AggregatorHealth, AggregatorMetricsSnapshot, AggregatorRuntime,
+    AggregatorSettings, AggregatorSettingsError, AggregatorStatus, OwnedCallbackError,
+    RpcAggregatorWithResultCallback, RpcAggregatorWithResultOwnedCallback,
+};
+use rust_extensions::{ApplicationStates, Logger};
 
 use super::{
-    rcp_aggregator_inner::RpcAggregatorInner,
-    rpc_request_data::{RcpRequestData, Request},
+    rpc_aggregator_with_result_owned_strategy::RpcAggregatorWithResultOwnedStrategy,
+    rpc_aggregator_with_result_strategy::RpcAggregatorWithResultStrategy,
+    rpc_request_data::Request, RpcAggregatorWithResultBuilder,
 };
 
 pub struct RpcAggregatorWithResult<
-    TItem: Send + Sync + 'static,
-    TResult: Send + Sync + 'static,
+    TItem: Send + 'static,
+    TResult: Send + 'static,
     TError: Send + Sync + 'static,
 > {
-    inner: Arc<(
-        Mutex<RpcAggregatorInner<TItem, TResult, TError>>,
-        AtomicUsize,
-    )>,
-    sender: tokio::sync::mpsc::UnboundedSender<()>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    name: String,
-    max_amount_per_round_trip: usize,
-    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
-    pub tick_timeout: std::time::Duration,
+    engine: BatchEngine<Request<TItem, TResult, TError>>,
 }
 
-impl<
-        TItem: Send + Sync + 'static,
-        TResult: Send + Sync + 'static,
-        TError: Send + Sync + 'static,
-    > RpcAggregatorWithResult<TItem, TResult, TError>
+impl<TItem: Send + 'static, TResult: Send + 'static, TError: Send + Sync + 'static>
+    RpcAggregatorWithResult<TItem, TResult, TError>
 {
     pub fn new(
         name: String,
@@ -39,35 +32,75 @@ impl<
         app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
         logger: Arc<dyn Logger + Send + Sync + 'static>,
     ) -> Self {
-        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
-
-        Self {
-            inner: Arc::new((
-                Mutex::new(RpcAggregatorInner::new(receiver)),
-                AtomicUsize::new(0),
-            )),
-            sender,
-            logger,
+        Self::from_settings(
             name,
-            max_amount_per_round_trip,
-            tick_timeout: std::time::Duration::from_secs(10),
+            AggregatorSettings::new(max_amount_per_round_trip),
             app_states,
-        }
+            logger,
+        )
+        .expect("max_amount_per_round_trip must be greater than 0")
+    }
+
+    pub fn builder(
+        name: String,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> RpcAggregatorWithResultBuilder<TItem, TResult, TError> {
+        RpcAggregatorWithResultBuilder::new(name, app_states, logger)
+    }
+
+    pub fn from_settings(
+        name: String,
+        settings: AggregatorSettings,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Result<Self, AggregatorSettingsError> {
+        let engine = BatchEngine::new(name, settings, app_states, logger)?;
+        Ok(Self { engine })
+    }
+
+    pub(crate) fn from_engine(engine: BatchEngine<Request<TItem, TResult, TError>>) -> Self {
+        Self { engine }
     }
 
     pub fn get_count(&self) -> usize {
-        self.inner.1.load(std::sync::atomic::Ordering::Relaxed)
+        self.engine.get_count()
     }
 
-    async fn get_receiver(&self) -> tokio::sync::mpsc::UnboundedReceiver<()> {
-        let mut write_access = self.inner.0.lock().await;
-        let result = write_access.receiver.take();
+    pub fn get_aborted_attempts(&self) -> u64 {
+        self.engine.get_aborted_attempts()
+    }
 
-        if result.is_none() {
-            panic!("You can not start RoundTripPusher twice");
-        }
+    pub fn get_metrics(&self) -> AggregatorMetricsSnapshot {
+        self.engine.get_metrics()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.engine.is_running()
+    }
 
-        result.unwrap()
+    pub fn status(&self) -> AggregatorStatus {
+        self.engine.status()
+    }
+
+    pub fn health(&self) -> AggregatorHealth {
+        self.engine.health()
+    }
+
+    pub fn get_settings(&self) -> AggregatorSettings {
+        self.engine.get_settings()
+    }
+
+    pub fn reconfigure(&self, settings: AggregatorSettings) -> Result<(), AggregatorSettingsError> {
+        self.engine.reconfigure(settings)
+    }
+
+    pub fn register_events(&mut self, events: Arc<dyn AggregatorEvents + Send + Sync + 'static>) {
+        self.engine.register_events(events);
+    }
+
+    pub fn set_runtime(&mut self, runtime: Arc<dyn AggregatorRuntime + Send + Sync + 'static>) {
+        self.engine.set_runtime(runtime);
     }
 
     pub async fn start(
@@ -76,64 +109,116 @@ impl<
             dyn RpcAggregatorWithResultCallback<TItem, TResult, TError> + Send + Sync + 'static,
         >,
     ) {
-        let receiver = self.get_receiver().await;
+        self.engine
+            .start(RpcAggregatorWithResultStrategy::from_callback(callback));
+    }
 
-        let name = self.name.clone();
-        tokio::spawn(read_loop(
-            name,
-            self.inner.clone(),
-            self.logger.clone(),
-            callback,
-            self.max_amount_per_round_trip,
-            self.tick_timeout,
-            receiver,
-        ));
+    // The closure owns the batch. OwnedCallbackError::Retry hands the items back.
+    pub async fn start_with_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<Vec<TResult>, OwnedCallbackError<TItem, TError>>>
+            + Send
+            + 'static,
+    {
+        self.start_owned(Arc::new(callback)).await;
     }
 
-    pub async fn execute_request(
+    // The lease hands the items back when dropped, so failed attempts are retried
+    pub async fn start_with_lease_fn<TFn, TFuture>(&self, callback: TFn)
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<Vec<TResult>, TError>> + Send + 'static,
+    {
+        self.engine
+            .start(RpcAggregatorWithResultStrategy::from_fn(callback));
+    }
+
+    pub async fn start_owned(
+        &self,
+        callback: Arc<
+            dyn RpcAggregatorWithResultOwnedCallback<TItem, TResult, TError>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    ) {
+        self.start_owned_sync(callback);
+    }
+
+    pub(crate) fn start_owned_sync(
+        &self,
+        callback: Arc<
+            dyn RpcAggregatorWithResultOwnedCallback<TItem, TResult, TError>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    ) {
+        self.engine
+            .start(RpcAggregatorWithResultOwnedStrategy::new(callback));
+    }
+
+    #[cfg(feature = "with-tower")]
+    pub(crate) fn get_capacity(&self) -> Option<Arc<tokio::sync::Semaphore>> {
+        self.engine.get_capacity()
+    }
+
+    #[cfg(feature = "with-tower")]
+    pub(crate) async fn execute_reserved(
         &self,
         data: TItem,
+        permit: Option<tokio::sync::OwnedSemaphorePermit>,
         #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
     ) -> Result<TResult, Arc<TError>> {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
-
-        let mut event = Request {
+        let mut request = Request {
             request_data: vec![data],
             completion: TaskCompletion::new(),
             #[cfg(feature = "with-telemetry")]
             my_telemetry,
         };
 
-        let task_await = event.completion.get_awaiter();
+        let awaiter = request.completion.get_awaiter();
 
-        {
-            let mut write_access = self.inner.0.lock().await;
+        self.engine.enqueue_reserved(request, 1, permit).await;
 
-            if write_access.receiver.is_some() {
-                panic!("Rcp aggregator {} is not started", self.name);
-            }
+        let mut result = awaiter.get_result().await?;
+        Ok(result.remove(0))
+    }
 
-            write_access.queue.push(event);
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+    pub async fn execute_request(
+        &self,
+        data: TItem,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Result<TResult, Arc<TError>> {
+        let mut result = self
+            .execute_multi_requests(
+                vec![data],
+                #[cfg(feature = "with-telemetry")]
+                my_telemetry,
+            )
+            .await?;
+
+        Ok(result.remove(0))
+    }
+
+    pub fn execute_request_blocking(
+        &self,
+        data: TItem,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Result<TResult, Arc<TError>> {
+        let mut request = Request {
+            request_data: vec![data],
+            completion: TaskCompletion::new(),
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+        };
+
+        let awaiter = request.completion.get_awaiter();
 
-        let mut result = task_await.get_result().await?;
+        self.engine.enqueue_blocking(std::iter::once((request, 1)));
 
+        let mut result = crate::block_on::block_on(awaiter.get_result())?;
         Ok(result.remove(0))
     }
 
@@ -159,42 +244,20 @@ impl<
         data: Vec<TItem>,
         #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
     ) -> Result<Vec<TResult>, Arc<TError>> {
-        if self.app_states.is_shutting_down() {
-            panic!(
-                "Can not publish to RoundTripPusher {} when shutting down",
-                self.name
-            );
-        }
+        let items_amount = data.len();
 
-        let mut event = Request {
+        let mut request = Request {
             request_data: data,
             completion: TaskCompletion::new(),
             #[cfg(feature = "with-telemetry")]
             my_telemetry,
         };
 
-        let awaiter = event.completion.get_awaiter();
+        let awaiter = request.completion.get_awaiter();
 
-        {
-            let mut write_access = self.inner.0.lock().await;
-
-            if write_access.receiver.is_some() {
-                panic!("Rcp aggregator {} is not started", self.name);
-            }
-
-            write_access.queue.push(event);
-            self.inner.1.store(
-                write_access.queue.len(),
-                std::sync::atomic::Ordering::SeqCst,
-            );
-        }
-        if self.sender.send(()).is_err() {
-            self.logger.write_fatal_error(
-                format!("publish to pusher {}", self.name),
-                "can not send".to_string(),
-                None,
-            );
-        }
+        self.engine
+            .enqueue(std::iter::once((request, items_amount)))
+            .await;
 
         awaiter.get_result().await
     }
@@ -222,170 +285,3 @@ impl<
         Ok(result)
     }
 }
-
-async fn read_loop<
-    TItem: Send + Sync + 'static,
-    TResult: Send + Sync + 'static,
-    TError: Send + Sync + 'static,
->(
-    name: String,
-    inner: Arc<(
-        Mutex<RpcAggregatorInner<TItem, TResult, TError>>,
-        AtomicUsize,
-    )>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
-    callback: Arc<
-        dyn RpcAggregatorWithResultCallback<TItem, TResult, TError> + Send + Sync + 'static,
-    >,
-    max_amount_per_round_trip: usize,
-    tick_timeout: std::time::Duration,
-    mut receiver: tokio::sync::mpsc::UnboundedReceiver<()>,
-) {
-    loop {
-        let to_publish = {
-            let mut write_access = inner.0.lock().await;
-
-            if write_access.queue.len() == 0 {
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-                None
-            } else if write_access.queue.len() > max_amount_per_round_trip {
-                let mut to_yield = Vec::with_capacity(max_amount_per_round_trip);
-                #[cfg(feature = "with-telemetry")]
-                let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
-
-                while to_yield.len() < max_amount_per_round_trip {
-                    let item = write_access.queue.remove(0);
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.add(&item.my_telemetry);
-                    to_yield.push(item);
-                }
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-
-                Some(RcpRequestData::new(
-                    to_yield,
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.compile(),
-                ))
-            } else {
-                let mut result = Vec::new();
-                std::mem::swap(&mut write_access.queue, &mut result);
-
-                inner.1.store(
-                    write_access.queue.len(),
-                    std::sync::atomic::Ordering::SeqCst,
-                );
-
-                #[cfg(feature = "with-telemetry")]
-                let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
-                #[cfg(feature = "with-telemetry")]
-                for item in &result {
-                    ctx_compiler.add(&item.my_telemetry);
-                }
-
-                Some(RcpRequestData::new(
-                    result,
-                    #[cfg(feature = "with-telemetry")]
-                    ctx_compiler.compile(),
-                ))
-            }
-        };
-
-        if let Some(mut to_publish) = to_publish {
-            let data_to_callback = to_publish.get_data_to_callback();
-            #[cfg(feature = "with-telemetry")]
-            let my_telemetry = to_publish.get_telemetry();
-
-            let mut attempt_no = 0;
-            loop {
-                let cloned = data_to_callback.clone();
-                #[cfg(feature = "with-telemetry")]
-                let my_telemetry_cloned = my_telemetry.clone();
-                let callback = callback.clone();
-
-                let future = tokio::spawn(async move {
-                    callback
-                        .handle(
-                            cloned.as_ref(),
-                            #[cfg(feature = "with-telemetry")]
-                            my_telemetry_cloned.as_ref(),
-                        )
-                        .await
-                });
-
-                let result = tokio::time::timeout(tick_timeout, future).await;
-
-                attempt_no += 1;
-
-                if result.is_err() {
-                    if attempt_no >= 5 {
-                        logger.write_fatal_error(
-                            format!("round trip pusher {}", name),
-                            format!("Attempt {}. Skipping items", attempt_no),
-                            None,
-                        );
-
-                        to_publish.set_panic("Timeout");
-
-                        break;
-                    }
-
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} timeout", attempt_no),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                let result = result.unwrap();
-
-                if let Err(err) = &result {
-                    if attempt_no >= 5 {
-                        logger.write_fatal_error(
-                            format!("round trip pusher {}", name),
-                            format!("Attempt {}. Skipping items", attempt_no),
-                            None,
-                        );
-
-                        to_publish.set_panic(format!("{}", err).as_str());
-
-                        break;
-                    }
-
-                    logger.write_fatal_error(
-                        format!("round trip pusher {}", name),
-                        format!("Attempt {} panic. Err: {:?}", attempt_no, err),
-                        None,
-                    );
-
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                match result.unwrap() {
-                    Ok(results) => {
-                        if let Err(message) = to_publish.set_results(results) {
-                            to_publish.set_panic(message.as_str());
-                        }
-                        break;
-                    }
-                    Err(err) => {
-                        to_publish.set_error(err);
-                        break;
-                    }
-                }
-            }
-        } else {
-            receiver.recv().await;
-        }
-    }
-}
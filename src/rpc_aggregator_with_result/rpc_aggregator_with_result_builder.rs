@@ -0,0 +1,16 @@
+use crate::{AggregatorBuilder, AggregatorSettingsError};
+
+use super::RpcAggregatorWithResult;
+
+pub type RpcAggregatorWithResultBuilder<TItem, TResult, TError> =
+    AggregatorBuilder<RpcAggregatorWithResult<TItem, TResult, TError>>;
+
+impl<TItem: Send + 'static, TResult: Send + 'static, TError: Send + Sync + 'static>
+    AggregatorBuilder<RpcAggregatorWithResult<TItem, TResult, TError>>
+{
+    pub fn build(
+        self,
+    ) -> Result<RpcAggregatorWithResult<TItem, TResult, TError>, AggregatorSettingsError> {
+        Ok(RpcAggregatorWithResult::from_engine(self.build_engine()?))
+    }
+}
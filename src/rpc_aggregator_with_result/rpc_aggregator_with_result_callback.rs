@@ -1,3 +1,5 @@
+use std::future::Future;
+
 #[async_trait::async_trait]
 pub trait RpcAggregatorWithResultCallback<TItem, TResult, TError> {
     async fn handle(
@@ -6,3 +8,21 @@ pub trait RpcAggregatorWithResultCallback<TItem, TResult, TError> {
         #[cfg(feature = "with-telemetry")] items: &my_telemetry::MyTelemetryContext,
     ) -> Result<Vec<TResult>, TError>;
 }
+
+// Closures do not get the telemetry context. ItemsLease::get_telemetry has it.
+#[async_trait::async_trait]
+impl<TItem, TResult, TError, TFn, TFuture> RpcAggregatorWithResultCallback<TItem, TResult, TError>
+    for TFn
+where
+    TItem: Sync,
+    TFn: Fn(&[TItem]) -> TFuture + Send + Sync,
+    TFuture: Future<Output = Result<Vec<TResult>, TError>> + Send + 'static,
+{
+    async fn handle(
+        &self,
+        items: &[TItem],
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<Vec<TResult>, TError> {
+        self(items).await
+    }
+}
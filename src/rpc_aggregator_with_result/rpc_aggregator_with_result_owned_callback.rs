@@ -0,0 +1,29 @@
+use std::future::Future;
+
+use crate::OwnedCallbackError;
+
+#[async_trait::async_trait]
+pub trait RpcAggregatorWithResultOwnedCallback<TItem, TResult, TError> {
+    async fn handle(
+        &self,
+        items: Vec<TItem>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<Vec<TResult>, OwnedCallbackError<TItem, TError>>;
+}
+
+#[async_trait::async_trait]
+impl<TItem, TResult, TError, TFn, TFuture>
+    RpcAggregatorWithResultOwnedCallback<TItem, TResult, TError> for TFn
+where
+    TItem: Send + 'static,
+    TFn: Fn(Vec<TItem>) -> TFuture + Send + Sync,
+    TFuture: Future<Output = Result<Vec<TResult>, OwnedCallbackError<TItem, TError>>> + Send,
+{
+    async fn handle(
+        &self,
+        items: Vec<TItem>,
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<Vec<TResult>, OwnedCallbackError<TItem, TError>> {
+        self(items).await
+    }
+}
@@ -0,0 +1,116 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    batch_engine::{
+        BatchCompletion, CompletionReporter, CompletionStrategy, ItemsLease, ItemsSlot,
+        LeaseCallback,
+    },
+    RpcAggregatorWithResultCallback,
+};
+
+use super::rpc_request_data::{RcpRequestData, Request};
+
+pub struct RpcAggregatorWithResultStrategy<
+    TItem: Send + 'static,
+    TResult: Send + 'static,
+    TError: Send + Sync + 'static,
+> {
+    callback: LeaseCallback<TItem, Result<Vec<TResult>, TError>>,
+}
+
+impl<TItem: Send + 'static, TResult: Send + 'static, TError: Send + Sync + 'static>
+    RpcAggregatorWithResultStrategy<TItem, TResult, TError>
+{
+    pub fn from_callback(
+        callback: Arc<
+            dyn RpcAggregatorWithResultCallback<TItem, TResult, TError> + Send + Sync + 'static,
+        >,
+    ) -> Self {
+        Self::from_fn(move |items: ItemsLease<TItem>| {
+            let callback = callback.clone();
+            async move {
+                callback
+                    .handle(
+                        &items,
+                        #[cfg(feature = "with-telemetry")]
+                        items.get_telemetry(),
+                    )
+                    .await
+            }
+        })
+    }
+
+    pub fn from_fn<TFn, TFuture>(callback: TFn) -> Self
+    where
+        TFn: Fn(ItemsLease<TItem>) -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<Vec<TResult>, TError>> + Send + 'static,
+    {
+        Self {
+            callback: Arc::new(move |items| Box::pin(callback(items))),
+        }
+    }
+}
+
+impl<TItem: Send + 'static, TResult: Send + 'static, TError: Send + Sync + 'static>
+    CompletionStrategy for RpcAggregatorWithResultStrategy<TItem, TResult, TError>
+{
+    type Request = Request<TItem, TResult, TError>;
+    type Batch = (RcpRequestData<TResult, TError>, ItemsSlot<TItem>);
+    type Output = Result<Vec<TResult>, TError>;
+
+    fn create_batch(&self, requests: Vec<Self::Request>) -> Self::Batch {
+        let (request_data, items) = RcpRequestData::new(requests);
+        (request_data, ItemsSlot::new(items))
+    }
+
+    fn create_callback_future(
+        &self,
+        batch: &mut Self::Batch,
+    ) -> Pin<Box<dyn Future<Output = Self::Output> + Send + 'static>> {
+        let data = batch.1.clone();
+        #[cfg(feature = "with-telemetry")]
+        let my_telemetry = batch.0.get_telemetry();
+        let callback = self.callback.clone();
+
+        Box::pin(async move {
+            let lease = data.lease(
+                #[cfg(feature = "with-telemetry")]
+                my_telemetry,
+            );
+            callback(lease).await
+        })
+    }
+
+    fn complete(
+        &self,
+        (mut batch, _): Self::Batch,
+        output: Self::Output,
+        reporter: &CompletionReporter,
+    ) -> BatchCompletion<Self::Batch> {
+        match output {
+            Ok(results) => match batch.set_results(results, reporter) {
+                Ok(_) => BatchCompletion::Delivered,
+                Err(message) => {
+                    batch.set_panic(message.as_str(), reporter);
+                    BatchCompletion::Failed(message)
+                }
+            },
+            Err(err) => {
+                batch.set_error(err, reporter);
+                BatchCompletion::Failed("Callback returned an error".to_string())
+            }
+        }
+    }
+
+    fn can_retry(&self, batch: &Self::Batch) -> bool {
+        !batch.1.is_lost()
+    }
+
+    fn items_returned(&self, batch: &Self::Batch) -> bool {
+        !batch.1.is_empty()
+    }
+
+    fn drop_batch(&self, (batch, _): Self::Batch, reason: &str, reporter: &CompletionReporter) {
+        batch.set_panic(reason, reporter);
+    }
+}
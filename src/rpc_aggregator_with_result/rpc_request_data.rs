@@ -1,12 +1,8 @@
 use std::sync::Arc;
 
-use rust_extensions::TaskCompletion;
+use crate::{batch_engine::CompletionReporter, task_completion::TaskCompletion};
 
-pub struct Request<
-    TItem: Send + Sync + 'static,
-    TResult: Send + Sync + 'static,
-    TError: Send + Sync + 'static,
-> {
+pub struct Request<TItem: Send + 'static, TResult: Send + 'static, TError: Send + Sync + 'static> {
     pub request_data: Vec<TItem>,
     pub completion: TaskCompletion<Vec<TResult>, Arc<TError>>,
 
@@ -14,63 +10,53 @@ pub struct Request<
     pub my_telemetry: my_telemetry::MyTelemetryContext,
 }
 
-pub struct RcpRequestData<
-    TItem: Send + Sync + 'static,
-    TResult: Send + Sync + 'static,
-    TError: Send + Sync + 'static,
-> {
-    data: Option<Vec<TItem>>,
+pub struct RcpRequestData<TResult: Send + 'static, TError: Send + Sync + 'static> {
     completions: Vec<(usize, TaskCompletion<Vec<TResult>, Arc<TError>>)>,
     amount: usize,
     #[cfg(feature = "with-telemetry")]
-    my_telemetry: Option<my_telemetry::MyTelemetryContext>,
+    my_telemetry: Arc<my_telemetry::MyTelemetryContext>,
 }
 
-impl<
-        TItem: Send + Sync + 'static,
-        TResult: Send + Sync + 'static,
-        TError: Send + Sync + 'static,
-    > RcpRequestData<TItem, TResult, TError>
-{
-    pub fn new(
+impl<TResult: Send + 'static, TError: Send + Sync + 'static> RcpRequestData<TResult, TError> {
+    pub fn new<TItem: Send + 'static>(
         requests: Vec<Request<TItem, TResult, TError>>,
-        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
-    ) -> Self {
+    ) -> (Self, Vec<TItem>) {
         let mut data = Vec::new();
         let mut completions = Vec::with_capacity(requests.len());
+        #[cfg(feature = "with-telemetry")]
+        let mut ctx_compiler = my_telemetry::MyTelemetryCompiler::new();
 
         let mut amount = 0;
 
         for request in requests {
+            #[cfg(feature = "with-telemetry")]
+            ctx_compiler.add(&request.my_telemetry);
             let chunk_size = request.request_data.len();
             amount += request.request_data.len();
             data.extend(request.request_data);
             completions.push((chunk_size, request.completion));
         }
 
-        Self {
-            data: Some(data),
+        let result = Self {
             completions,
             amount,
             #[cfg(feature = "with-telemetry")]
-            my_telemetry: Some(my_telemetry),
-        }
-    }
+            my_telemetry: Arc::new(ctx_compiler.compile()),
+        };
 
-    pub fn get_data_to_callback(&mut self) -> Arc<Vec<TItem>> {
-        let mut new_result = None;
-        std::mem::swap(&mut new_result, &mut self.data);
-        Arc::new(new_result.unwrap())
+        (result, data)
     }
 
     #[cfg(feature = "with-telemetry")]
-    pub fn get_telemetry(&mut self) -> Arc<my_telemetry::MyTelemetryContext> {
-        let mut new_result = None;
-        std::mem::swap(&mut new_result, &mut self.my_telemetry);
-        Arc::new(new_result.unwrap())
+    pub fn get_telemetry(&self) -> Arc<my_telemetry::MyTelemetryContext> {
+        self.my_telemetry.clone()
     }
 
-    pub fn set_results(&mut self, mut results: Vec<TResult>) -> Result<(), String> {
+    pub fn set_results(
+        &mut self,
+        results: Vec<TResult>,
+        reporter: &CompletionReporter,
+    ) -> Result<(), String> {
         if results.len() != self.amount {
             return Err(format!(
                 "amount of results [{}] != amount of requests [{}]",
@@ -79,38 +65,162 @@ impl<
             ));
         }
 
-        for (amount, completion) in &mut self.completions {
-            let mut chunk = Vec::with_capacity(*amount);
-            for _ in 0..*amount {
-                let result = results.remove(0);
-                chunk.push(result);
-            }
-
+        distribute_chunks(&mut self.completions, results, |completion, chunk| {
+            // The caller may be gone already, the rest of the batch still gets its results
             if let Err(err) = completion.try_set_ok(chunk) {
-                panic!(
-                    "Can not set ok result to request completion with result: {:?}",
-                    err
-                );
+                reporter.report_unset("Ok", err);
             }
-        }
+        });
 
         Ok(())
     }
 
-    pub fn set_panic(mut self, message: &str) {
+    pub fn set_panic(mut self, message: &str, reporter: &CompletionReporter) {
         for (_, completion) in &mut self.completions {
             if let Err(err) = completion.try_set_panic(message.to_string()) {
-                println!("Can not set panic result to the task completion. {:?}", err);
+                reporter.report_unset("panic", err);
             }
         }
     }
 
-    pub fn set_error(mut self, err: TError) {
+    pub fn set_error(mut self, err: TError, reporter: &CompletionReporter) {
         let err = Arc::new(err);
         for (_, completion) in &mut self.completions {
             if let Err(err) = completion.try_set_error(err.clone()) {
-                println!("set_error: {:?}", err);
+                reporter.report_unset("error", err);
             }
         }
     }
 }
+
+// Every result is taken from the iterator once and every completion gets one chunk,
+// so the distribution stays linear in the batch size.
+fn distribute_chunks<TResult, TCompletion>(
+    completions: &mut [(usize, TCompletion)],
+    results: impl IntoIterator<Item = TResult>,
+    mut set_chunk: impl FnMut(&mut TCompletion, Vec<TResult>),
+) {
+    let mut results = results.into_iter();
+
+    for (amount, completion) in completions {
+        let chunk: Vec<TResult> = results.by_ref().take(*amount).collect();
+        set_chunk(completion, chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use rust_extensions::Logger;
+
+    use super::{distribute_chunks, RcpRequestData};
+    use crate::{
+        batch_engine::CompletionReporter,
+        task_completion::{TaskCompletion, TaskCompletionAwaiter},
+    };
+
+    const CHUNK_SIZE: usize = 100;
+
+    type ChunkAwaiter = TaskCompletionAwaiter<Vec<usize>, Arc<String>>;
+
+    struct NoLogger;
+
+    impl Logger for NoLogger {
+        fn write_info(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+        fn write_warning(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+        fn write_error(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+        fn write_fatal_error(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+    }
+
+    fn set_results(
+        request_data: &mut RcpRequestData<usize, String>,
+        results: Vec<usize>,
+    ) -> Result<(), String> {
+        request_data.set_results(results, &CompletionReporter::new("test", &NoLogger))
+    }
+
+    fn create_request_data(amount: usize) -> (RcpRequestData<usize, String>, Vec<ChunkAwaiter>) {
+        let mut completions = Vec::new();
+        let mut awaiters = Vec::new();
+
+        for _ in 0..amount / CHUNK_SIZE {
+            let mut completion = TaskCompletion::new();
+            awaiters.push(completion.get_awaiter());
+            completions.push((CHUNK_SIZE, completion));
+        }
+
+        let request_data = RcpRequestData {
+            completions,
+            amount,
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry: Arc::new(my_telemetry::MyTelemetryCompiler::new().compile()),
+        };
+
+        (request_data, awaiters)
+    }
+
+    #[tokio::test]
+    async fn test_results_are_distributed_in_order() {
+        let (mut request_data, awaiters) = create_request_data(CHUNK_SIZE * 3);
+
+        set_results(&mut request_data, (0..CHUNK_SIZE * 3).collect()).unwrap();
+
+        for (index, awaiter) in awaiters.into_iter().enumerate() {
+            let expected: Vec<usize> = (index * CHUNK_SIZE..(index + 1) * CHUNK_SIZE).collect();
+            assert_eq!(awaiter.get_result().await.unwrap(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_batch_is_distributed_in_order() {
+        let amount = CHUNK_SIZE * 640;
+        let (mut request_data, awaiters) = create_request_data(amount);
+
+        set_results(&mut request_data, (0..amount).collect()).unwrap();
+
+        for (index, awaiter) in awaiters.into_iter().enumerate() {
+            let result = awaiter.get_result().await.unwrap();
+            assert_eq!(result.len(), CHUNK_SIZE);
+            assert_eq!(result[0], index * CHUNK_SIZE);
+            assert_eq!(result[CHUNK_SIZE - 1], (index + 1) * CHUNK_SIZE - 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gone_caller_does_not_stop_distribution() {
+        let (mut request_data, mut awaiters) = create_request_data(CHUNK_SIZE * 2);
+        drop(awaiters.remove(0));
+
+        set_results(&mut request_data, (0..CHUNK_SIZE * 2).collect()).unwrap();
+
+        let result = awaiters.remove(0).get_result().await.unwrap();
+        assert_eq!(result[0], CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_wrong_amount_of_results() {
+        let (mut request_data, _awaiters) = create_request_data(CHUNK_SIZE);
+        assert!(set_results(&mut request_data, vec![0]).is_err());
+    }
+
+    #[test]
+    fn test_distribution_touches_every_result_and_completion_once() {
+        let amount = 64_000;
+        // Amount of items per completion and how many chunks it got
+        let mut completions: Vec<(usize, usize)> = (0..amount / 16).map(|_| (16, 0)).collect();
+        let mut taken = 0;
+
+        distribute_chunks(
+            &mut completions,
+            (0..amount).inspect(|_| taken += 1),
+            |chunks, chunk| {
+                assert_eq!(chunk.len(), 16);
+                *chunks += 1;
+            },
+        );
+
+        assert_eq!(taken, amount);
+        assert!(completions.iter().all(|(_, chunks)| *chunks == 1));
+    }
+}
@@ -0,0 +1,24 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+pub type RuntimeFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+
+pub trait AggregatorRuntime {
+    fn spawn(&self, future: RuntimeFuture<()>) -> Box<dyn SpawnedTask + Send + Sync + 'static>;
+    fn sleep(&self, duration: Duration) -> RuntimeFuture<()>;
+}
+
+// Dropping the task detaches it. Only abort cancels it.
+pub trait SpawnedTask {
+    fn abort(&self);
+}
+
+pub(crate) fn default_runtime() -> Option<Arc<dyn AggregatorRuntime + Send + Sync + 'static>> {
+    #[cfg(feature = "with-tokio")]
+    return Some(Arc::new(super::TokioRuntime));
+
+    #[cfg(all(feature = "with-smol", not(feature = "with-tokio")))]
+    return Some(Arc::new(super::SmolRuntime));
+
+    #[cfg(not(any(feature = "with-tokio", feature = "with-smol")))]
+    return None;
+}
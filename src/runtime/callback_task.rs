@@ -0,0 +1,56 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::sync::oneshot;
+
+use super::{AggregatorRuntime, CatchPanic, RuntimeFuture, SpawnedTask};
+
+// Callback spawned on the runtime. Resolves to the output of the callback, or to the
+// panic message if the callback panicked or was aborted.
+pub(crate) struct CallbackTask<TOutput> {
+    task: Box<dyn SpawnedTask + Send + Sync + 'static>,
+    receiver: oneshot::Receiver<Result<TOutput, String>>,
+}
+
+impl<TOutput: Send + 'static> CallbackTask<TOutput> {
+    pub fn spawn(
+        runtime: &(dyn AggregatorRuntime + Send + Sync),
+        future: RuntimeFuture<TOutput>,
+    ) -> Self {
+        let (sender, receiver) = oneshot::channel();
+
+        let task = runtime.spawn(Box::pin(async move {
+            let result = CatchPanic::new(future).await;
+            let _ = sender.send(result);
+        }));
+
+        Self { task, receiver }
+    }
+
+    // True once the callback produced its result, including a panic. The result is discarded.
+    pub fn is_finished(&mut self) -> bool {
+        !matches!(
+            self.receiver.try_recv(),
+            Err(oneshot::error::TryRecvError::Empty)
+        )
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl<TOutput> Future for CallbackTask<TOutput> {
+    type Output = Result<TOutput, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err("task was cancelled".to_string())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
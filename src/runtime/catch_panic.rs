@@ -0,0 +1,41 @@
+use std::{
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// Resolves to the output of the future, or to the panic message if polling it panicked
+pub(crate) struct CatchPanic<TFuture: Future + Unpin> {
+    future: TFuture,
+}
+
+impl<TFuture: Future + Unpin> CatchPanic<TFuture> {
+    pub fn new(future: TFuture) -> Self {
+        Self { future }
+    }
+}
+
+impl<TFuture: Future + Unpin> Future for CatchPanic<TFuture> {
+    type Output = Result<TFuture::Output, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = Pin::new(&mut self.future);
+
+        match std::panic::catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+            Ok(Poll::Ready(result)) => Poll::Ready(Ok(result)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => {
+                let message = if let Some(message) = panic.downcast_ref::<&str>() {
+                    message.to_string()
+                } else if let Some(message) = panic.downcast_ref::<String>() {
+                    message.clone()
+                } else {
+                    "Box<dyn Any>".to_string()
+                };
+
+                Poll::Ready(Err(format!("task panicked with message {:?}", message)))
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+mod aggregator_runtime;
+mod callback_task;
+mod catch_panic;
+#[cfg(feature = "with-smol")]
+mod smol_runtime;
+mod task_abort;
+mod timeout;
+#[cfg(feature = "with-tokio")]
+mod tokio_runtime;
+
+pub use aggregator_runtime::*;
+pub(crate) use callback_task::*;
+pub(crate) use catch_panic::*;
+#[cfg(feature = "with-smol")]
+pub use smol_runtime::*;
+pub(crate) use task_abort::*;
+pub(crate) use timeout::*;
+#[cfg(feature = "with-tokio")]
+pub use tokio_runtime::*;
@@ -0,0 +1,34 @@
+use std::{sync::Mutex, time::Duration};
+
+use super::{AggregatorRuntime, RuntimeFuture, SpawnedTask};
+
+pub struct SmolRuntime;
+
+impl AggregatorRuntime for SmolRuntime {
+    fn spawn(&self, future: RuntimeFuture<()>) -> Box<dyn SpawnedTask + Send + Sync + 'static> {
+        Box::new(SmolTask(Mutex::new(Some(smol::spawn(future)))))
+    }
+
+    fn sleep(&self, duration: Duration) -> RuntimeFuture<()> {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+}
+
+// A smol task is cancelled when it is dropped, so it is detached unless aborted
+struct SmolTask(Mutex<Option<smol::Task<()>>>);
+
+impl SpawnedTask for SmolTask {
+    fn abort(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+impl Drop for SmolTask {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.lock().unwrap().take() {
+            task.detach();
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use super::{AggregatorRuntime, CallbackTask};
+
+// Returns false if the task had already finished, so there was nothing to abort
+pub(crate) async fn abort_timed_out_task<T: Send + 'static>(
+    runtime: &(dyn AggregatorRuntime + Send + Sync),
+    mut task: CallbackTask<T>,
+    grace_period: Option<Duration>,
+) -> bool {
+    if task.is_finished() {
+        return false;
+    }
+
+    task.abort();
+
+    if let Some(grace_period) = grace_period {
+        let _ = super::timeout(runtime, grace_period, task).await;
+    }
+
+    true
+}
+
+#[cfg(all(test, feature = "with-tokio"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::abort_timed_out_task;
+    use crate::runtime::{CallbackTask, TokioRuntime};
+
+    #[tokio::test]
+    async fn test_finished_task_is_not_aborted() {
+        let task = CallbackTask::spawn(&TokioRuntime, Box::pin(async {}));
+        tokio::task::yield_now().await;
+
+        assert!(!abort_timed_out_task(&TokioRuntime, task, None).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_running_task_is_aborted() {
+        let task = CallbackTask::spawn(&TokioRuntime, Box::pin(std::future::pending::<()>()));
+
+        assert!(abort_timed_out_task(&TokioRuntime, task, Some(Duration::from_secs(1))).await);
+    }
+}
@@ -0,0 +1,31 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+
+use super::AggregatorRuntime;
+
+// Returns None if the future did not complete in time
+pub(crate) async fn timeout<TFuture: Future>(
+    runtime: &(dyn AggregatorRuntime + Send + Sync),
+    duration: Duration,
+    future: TFuture,
+) -> Option<TFuture::Output> {
+    let mut future = pin!(future);
+    let mut sleep = runtime.sleep(duration);
+
+    poll_fn(|cx| {
+        if let Poll::Ready(result) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(result));
+        }
+
+        if sleep.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    })
+    .await
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use super::{AggregatorRuntime, RuntimeFuture, SpawnedTask};
+
+pub struct TokioRuntime;
+
+impl AggregatorRuntime for TokioRuntime {
+    fn spawn(&self, future: RuntimeFuture<()>) -> Box<dyn SpawnedTask + Send + Sync + 'static> {
+        Box::new(TokioTask(tokio::spawn(future)))
+    }
+
+    fn sleep(&self, duration: Duration) -> RuntimeFuture<()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+struct TokioTask(JoinHandle<()>);
+
+impl SpawnedTask for TokioTask {
+    fn abort(&self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,97 @@
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use rust_extensions::{ApplicationStates, Logger};
+
+use crate::{batch_engine::BatchEngine, AggregatorEvents, AggregatorRuntime};
+
+use super::{AggregatorSettings, AggregatorSettingsError, HealthRules, RetryPolicy};
+
+// Shared by all aggregators. Every aggregator module adds its own build method.
+pub struct AggregatorBuilder<TAggregator> {
+    name: String,
+    settings: AggregatorSettings,
+    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+    logger: Arc<dyn Logger + Send + Sync + 'static>,
+    events: Vec<Arc<dyn AggregatorEvents + Send + Sync + 'static>>,
+    runtime: Option<Arc<dyn AggregatorRuntime + Send + Sync + 'static>>,
+    aggregator: PhantomData<TAggregator>,
+}
+
+impl<TAggregator> AggregatorBuilder<TAggregator> {
+    pub fn new(
+        name: String,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+    ) -> Self {
+        Self {
+            name,
+            settings: AggregatorSettings::default(),
+            app_states,
+            logger,
+            events: Vec::new(),
+            runtime: None,
+            aggregator: PhantomData,
+        }
+    }
+
+    pub fn max_amount_per_round_trip(mut self, value: usize) -> Self {
+        self.settings.max_amount_per_round_trip = value;
+        self
+    }
+
+    pub fn linger(mut self, value: Duration) -> Self {
+        self.settings.linger = value;
+        self
+    }
+
+    pub fn tick_timeout(mut self, value: Duration) -> Self {
+        self.settings.tick_timeout = value;
+        self
+    }
+
+    pub fn abort_grace_period(mut self, value: Duration) -> Self {
+        self.settings.abort_grace_period = Some(value);
+        self
+    }
+
+    pub fn retry_policy(mut self, value: RetryPolicy) -> Self {
+        self.settings.retry_policy = value;
+        self
+    }
+
+    pub fn health_rules(mut self, value: HealthRules) -> Self {
+        self.settings.health_rules = value;
+        self
+    }
+
+    pub fn queue_capacity(mut self, value: usize) -> Self {
+        self.settings.queue_capacity = Some(value);
+        self
+    }
+
+    pub fn events(mut self, value: Arc<dyn AggregatorEvents + Send + Sync + 'static>) -> Self {
+        self.events.push(value);
+        self
+    }
+
+    pub fn runtime(mut self, value: Arc<dyn AggregatorRuntime + Send + Sync + 'static>) -> Self {
+        self.runtime = Some(value);
+        self
+    }
+
+    pub(crate) fn build_engine<TRequest: Send + 'static>(
+        self,
+    ) -> Result<BatchEngine<TRequest>, AggregatorSettingsError> {
+        let mut engine = BatchEngine::new(self.name, self.settings, self.app_states, self.logger)?;
+
+        for events in self.events {
+            engine.register_events(events);
+        }
+
+        if let Some(runtime) = self.runtime {
+            engine.set_runtime(runtime);
+        }
+
+        Ok(engine)
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{AggregatorSettingsError, HealthRules, RetryPolicy};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AggregatorSettings {
+    pub max_amount_per_round_trip: usize,
+    // How long the loop waits for a not yet full batch to fill up before sending it.
+    pub linger: Duration,
+    pub tick_timeout: Duration,
+    pub abort_grace_period: Option<Duration>,
+    pub retry_policy: RetryPolicy,
+    pub queue_capacity: Option<usize>,
+    pub health_rules: HealthRules,
+}
+
+impl AggregatorSettings {
+    pub fn new(max_amount_per_round_trip: usize) -> Self {
+        Self {
+            max_amount_per_round_trip,
+            linger: Duration::ZERO,
+            tick_timeout: Duration::from_secs(10),
+            abort_grace_period: None,
+            retry_policy: RetryPolicy::default(),
+            queue_capacity: None,
+            health_rules: HealthRules::default(),
+        }
+    }
+
+    pub fn validate(&self, name: &str) -> Result<(), AggregatorSettingsError> {
+        if self.max_amount_per_round_trip == 0 {
+            return Err(AggregatorSettingsError::ZeroMaxAmountPerRoundTrip {
+                name: name.to_string(),
+            });
+        }
+
+        if self.tick_timeout.is_zero() {
+            return Err(AggregatorSettingsError::ZeroTickTimeout {
+                name: name.to_string(),
+            });
+        }
+
+        if self.retry_policy.max_attempts == 0 {
+            return Err(AggregatorSettingsError::ZeroMaxAttempts {
+                name: name.to_string(),
+            });
+        }
+
+        if self.queue_capacity == Some(0) {
+            return Err(AggregatorSettingsError::ZeroQueueCapacity {
+                name: name.to_string(),
+            });
+        }
+
+        if self.health_rules.failure_threshold == Some(0) {
+            return Err(AggregatorSettingsError::ZeroFailureThreshold {
+                name: name.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AggregatorSettings {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AggregatorSettings, AggregatorSettingsError, HealthRules, HealthStatus};
+
+    #[test]
+    fn test_zero_failure_threshold_is_rejected() {
+        let mut settings = AggregatorSettings::new(10);
+        assert_eq!(settings.validate("test"), Ok(()));
+
+        settings.health_rules = HealthRules::new(None, Some(0), HealthStatus::Unhealthy);
+        assert_eq!(
+            settings.validate("test"),
+            Err(AggregatorSettingsError::ZeroFailureThreshold {
+                name: "test".to_string()
+            })
+        );
+
+        settings.health_rules = HealthRules::new(None, Some(1), HealthStatus::Unhealthy);
+        assert_eq!(settings.validate("test"), Ok(()));
+    }
+}
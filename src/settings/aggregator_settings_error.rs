@@ -0,0 +1,48 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregatorSettingsError {
+    ZeroMaxAmountPerRoundTrip { name: String },
+    ZeroTickTimeout { name: String },
+    ZeroMaxAttempts { name: String },
+    ZeroQueueCapacity { name: String },
+    QueueCapacityChanged { name: String },
+    ZeroFailureThreshold { name: String },
+}
+
+impl std::fmt::Display for AggregatorSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregatorSettingsError::ZeroMaxAmountPerRoundTrip { name } => write!(
+                f,
+                "Aggregator {}: max_amount_per_round_trip must be greater than 0",
+                name
+            ),
+            AggregatorSettingsError::ZeroTickTimeout { name } => write!(
+                f,
+                "Aggregator {}: tick_timeout must be greater than 0",
+                name
+            ),
+            AggregatorSettingsError::ZeroMaxAttempts { name } => write!(
+                f,
+                "Aggregator {}: retry policy must allow at least one attempt",
+                name
+            ),
+            AggregatorSettingsError::ZeroQueueCapacity { name } => write!(
+                f,
+                "Aggregator {}: queue_capacity must be greater than 0 when set",
+                name
+            ),
+            AggregatorSettingsError::QueueCapacityChanged { name } => write!(
+                f,
+                "Aggregator {}: queue_capacity can not be changed after the aggregator is created",
+                name
+            ),
+            AggregatorSettingsError::ZeroFailureThreshold { name } => write!(
+                f,
+                "Aggregator {}: failure_threshold must be greater than 0 when set",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AggregatorSettingsError {}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::HealthStatus;
+
+// Rules which turn an aggregator degraded or unhealthy. Every rule is off when not set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HealthRules {
+    pub max_oldest_item_age: Option<Duration>,
+    // Failed callback attempts in a row, including panics and timeouts, at which
+    // the aggregator gets failure_severity.
+    pub failure_threshold: Option<usize>,
+    pub failure_severity: HealthStatus,
+}
+
+impl HealthRules {
+    pub fn new(
+        max_oldest_item_age: Option<Duration>,
+        failure_threshold: Option<usize>,
+        failure_severity: HealthStatus,
+    ) -> Self {
+        Self {
+            max_oldest_item_age,
+            failure_threshold,
+            failure_severity,
+        }
+    }
+}
+
+impl Default for HealthRules {
+    fn default() -> Self {
+        Self::new(None, None, HealthStatus::Degraded)
+    }
+}
@@ -0,0 +1,11 @@
+mod aggregator_builder;
+mod aggregator_settings;
+mod aggregator_settings_error;
+mod health_rules;
+mod retry_policy;
+
+pub use aggregator_builder::*;
+pub use aggregator_settings::*;
+pub use aggregator_settings_error::*;
+pub use health_rules::*;
+pub use retry_policy::*;
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RetryPolicy {
+    // Total amount of callback attempts per batch including the first one.
+    pub max_attempts: usize,
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: usize, delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            delay,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            delay: Duration::from_secs(1),
+        }
+    }
+}
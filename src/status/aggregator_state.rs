@@ -0,0 +1,108 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Instant, SystemTime},
+};
+
+use super::{InFlightBatchStatus, LastErrorStatus};
+
+struct InFlightBatch {
+    batch_size: usize,
+    attempt_no: usize,
+    started: Instant,
+}
+
+pub struct AggregatorState {
+    started: AtomicBool,
+    running: AtomicBool,
+    consecutive_failures: AtomicUsize,
+    in_flight: Mutex<Option<InFlightBatch>>,
+    last_error: Mutex<Option<LastErrorStatus>>,
+}
+
+impl AggregatorState {
+    pub fn new() -> Self {
+        Self {
+            started: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            consecutive_failures: AtomicUsize::new(0),
+            in_flight: Mutex::new(None),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    // Returns false if the aggregator was already started
+    pub fn try_set_started(&self) -> bool {
+        !self.started.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    pub fn set_running(&self, value: bool) {
+        self.running.store(value, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn attempt_started(&self, batch_size: usize, attempt_no: usize) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        match in_flight.as_mut() {
+            Some(batch) => batch.attempt_no = attempt_no,
+            None => {
+                *in_flight = Some(InFlightBatch {
+                    batch_size,
+                    attempt_no,
+                    started: Instant::now(),
+                })
+            }
+        }
+    }
+
+    pub fn batch_finished(&self) {
+        *self.in_flight.lock().unwrap() = None;
+    }
+
+    pub fn get_in_flight(&self) -> Option<InFlightBatchStatus> {
+        let in_flight = self.in_flight.lock().unwrap();
+        in_flight.as_ref().map(|batch| InFlightBatchStatus {
+            batch_size: batch.batch_size,
+            attempt_no: batch.attempt_no,
+            elapsed: batch.started.elapsed(),
+        })
+    }
+
+    pub fn attempt_failed(&self, message: String) {
+        self.consecutive_failures.fetch_add(1, Ordering::SeqCst);
+        self.set_last_error(message);
+    }
+
+    pub fn attempt_succeeded(&self) {
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+    }
+
+    pub fn get_consecutive_failures(&self) -> usize {
+        self.consecutive_failures.load(Ordering::SeqCst)
+    }
+
+    pub fn set_last_error(&self, message: String) {
+        let unix_timestamp_micros = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_micros() as u64)
+            .unwrap_or_default();
+
+        *self.last_error.lock().unwrap() = Some(LastErrorStatus {
+            message,
+            unix_timestamp_micros,
+        });
+    }
+
+    pub fn get_last_error(&self) -> Option<LastErrorStatus> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
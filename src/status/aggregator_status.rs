@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::AggregatorSettings;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatorStatus {
+    pub name: String,
+    pub started: bool,
+    pub stopped: bool,
+    pub running: bool,
+    pub queued_requests: usize,
+    pub queued_items: usize,
+    pub oldest_item_age: Option<Duration>,
+    pub in_flight: Option<InFlightBatchStatus>,
+    pub last_error: Option<LastErrorStatus>,
+    pub consecutive_failures: usize,
+    pub config: AggregatorSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InFlightBatchStatus {
+    pub batch_size: usize,
+    pub attempt_no: usize,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LastErrorStatus {
+    pub message: String,
+    pub unix_timestamp_micros: u64,
+}
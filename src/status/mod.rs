@@ -0,0 +1,5 @@
+mod aggregator_state;
+mod aggregator_status;
+
+pub(crate) use aggregator_state::*;
+pub use aggregator_status::*;
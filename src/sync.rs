@@ -0,0 +1,50 @@
+// Synchronization primitives of the queue. Under `--cfg loom` they are replaced by the loom
+// versions, so the queue protocol can be model checked.
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    Mutex,
+};
+#[cfg(not(loom))]
+pub(crate) use std::sync::{
+    atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+    Mutex,
+};
+
+// Wakes up the single waiter. A notification sent while nobody waits is kept for the next wait.
+pub(crate) struct Wakeup {
+    #[cfg(not(loom))]
+    notify: tokio::sync::Notify,
+    #[cfg(loom)]
+    notify: loom::sync::Notify,
+}
+
+impl Wakeup {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(loom))]
+            notify: tokio::sync::Notify::new(),
+            #[cfg(loom)]
+            notify: loom::sync::Notify::new(),
+        }
+    }
+
+    pub fn notify(&self) {
+        #[cfg(not(loom))]
+        self.notify.notify_one();
+        #[cfg(loom)]
+        self.notify.notify();
+    }
+
+    #[cfg(not(loom))]
+    pub async fn wait(&self) {
+        self.notify.notified().await;
+    }
+
+    // Loom threads are blocked instead of parked by a runtime
+    #[cfg(loom)]
+    pub async fn wait(&self) {
+        self.notify.wait();
+    }
+}
@@ -0,0 +1,108 @@
+use tokio::sync::oneshot;
+
+// The result of a request, sent to the caller over a oneshot channel. Only the sync part
+// of tokio is used, so the callers can be awaited on any executor.
+enum TaskResult<TOk, TError> {
+    Ok(TOk),
+    Error(TError),
+    Panic(String),
+}
+
+#[derive(Debug)]
+pub enum TaskCompletionError {
+    AlreadyCompleted,
+    AwaiterIsGone,
+}
+
+pub struct TaskCompletion<TOk, TError> {
+    sender: Option<oneshot::Sender<TaskResult<TOk, TError>>>,
+    receiver: Option<oneshot::Receiver<TaskResult<TOk, TError>>>,
+}
+
+impl<TOk, TError> TaskCompletion<TOk, TError> {
+    pub fn new() -> Self {
+        let (sender, receiver) = oneshot::channel();
+        Self {
+            sender: Some(sender),
+            receiver: Some(receiver),
+        }
+    }
+
+    pub fn get_awaiter(&mut self) -> TaskCompletionAwaiter<TOk, TError> {
+        TaskCompletionAwaiter {
+            receiver: self
+                .receiver
+                .take()
+                .expect("Awaiter of the task completion is already taken"),
+        }
+    }
+
+    pub fn try_set_ok(&mut self, value: TOk) -> Result<(), TaskCompletionError> {
+        self.send(TaskResult::Ok(value))
+    }
+
+    pub fn try_set_error(&mut self, err: TError) -> Result<(), TaskCompletionError> {
+        self.send(TaskResult::Error(err))
+    }
+
+    pub fn try_set_panic(&mut self, message: String) -> Result<(), TaskCompletionError> {
+        self.send(TaskResult::Panic(message))
+    }
+
+    fn send(&mut self, result: TaskResult<TOk, TError>) -> Result<(), TaskCompletionError> {
+        let sender = self
+            .sender
+            .take()
+            .ok_or(TaskCompletionError::AlreadyCompleted)?;
+
+        sender
+            .send(result)
+            .map_err(|_| TaskCompletionError::AwaiterIsGone)
+    }
+}
+
+pub struct TaskCompletionAwaiter<TOk, TError> {
+    receiver: oneshot::Receiver<TaskResult<TOk, TError>>,
+}
+
+impl<TOk, TError> TaskCompletionAwaiter<TOk, TError> {
+    // Panics the same way as the callback did, so callers see a dropped batch as a panic
+    pub async fn get_result(self) -> Result<TOk, TError> {
+        match self.receiver.await {
+            Ok(TaskResult::Ok(value)) => Ok(value),
+            Ok(TaskResult::Error(err)) => Err(err),
+            Ok(TaskResult::Panic(message)) => panic!("{}", message),
+            Err(_) => panic!("Task completion is dropped without a result"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TaskCompletion, TaskCompletionError};
+
+    #[tokio::test]
+    async fn test_awaiter_gets_the_result() {
+        let mut completion: TaskCompletion<u32, String> = TaskCompletion::new();
+        let awaiter = completion.get_awaiter();
+
+        completion.try_set_error("Failed".to_string()).unwrap();
+
+        assert_eq!(awaiter.get_result().await.unwrap_err(), "Failed");
+        assert!(matches!(
+            completion.try_set_ok(1),
+            Err(TaskCompletionError::AlreadyCompleted)
+        ));
+    }
+
+    #[test]
+    fn test_gone_awaiter_is_reported() {
+        let mut completion: TaskCompletion<u32, String> = TaskCompletion::new();
+        drop(completion.get_awaiter());
+
+        assert!(matches!(
+            completion.try_set_ok(1),
+            Err(TaskCompletionError::AwaiterIsGone)
+        ));
+    }
+}
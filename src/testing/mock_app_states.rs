@@ -0,0 +1,35 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rust_extensions::ApplicationStates;
+
+pub struct MockAppStates {
+    shutting_down: AtomicBool,
+}
+
+impl MockAppStates {
+    pub fn new() -> Self {
+        Self {
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    pub fn set_shutting_down(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Default for MockAppStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApplicationStates for MockAppStates {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+}
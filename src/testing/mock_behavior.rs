@@ -0,0 +1,10 @@
+use std::time::Duration;
+
+pub enum MockBehavior<TError> {
+    Succeed,
+    Fail(TError),
+    Panic(String),
+    // Never completes, so the attempt ends with tick_timeout
+    Hang,
+    Delay(Duration),
+}
@@ -0,0 +1,192 @@
+use crate::{RoundTripCallback, RpcAggregatorCallback};
+
+use super::{MockBehavior, MockRecorder};
+
+// Records every batch it is called with. Scripted behaviors are applied to the calls in order,
+// calls after the end of the script succeed.
+pub struct MockCallback<TItem, TError = String> {
+    recorder: MockRecorder<TItem, TError>,
+}
+
+impl<TItem: Clone, TError> MockCallback<TItem, TError> {
+    pub fn new() -> Self {
+        Self {
+            recorder: MockRecorder::new(),
+        }
+    }
+
+    pub fn then(self, behavior: MockBehavior<TError>) -> Self {
+        self.recorder.push_behavior(behavior);
+        self
+    }
+
+    pub fn push_behavior(&self, behavior: MockBehavior<TError>) {
+        self.recorder.push_behavior(behavior);
+    }
+
+    pub fn get_batches(&self) -> Vec<Vec<TItem>> {
+        self.recorder.get_batches()
+    }
+
+    pub fn get_calls_count(&self) -> usize {
+        self.recorder.get_calls_count()
+    }
+
+    pub async fn wait_for_calls(&self, amount: usize) {
+        self.recorder.wait_for_calls(amount).await;
+    }
+}
+
+impl<TItem: Clone, TError> Default for MockCallback<TItem, TError> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Round trip callbacks can not return an error, so a scripted failure panics
+#[async_trait::async_trait]
+impl<TItem: Clone + Send + Sync + 'static, TError: Send + 'static> RoundTripCallback<TItem>
+    for MockCallback<TItem, TError>
+{
+    async fn handle(&self, items: &[TItem]) {
+        if self.recorder.record(items).await.is_err() {
+            panic!("Mock callback failed");
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<TItem: Clone + Send + Sync + 'static, TError: Send + 'static>
+    RpcAggregatorCallback<TItem, TError> for MockCallback<TItem, TError>
+{
+    async fn handle(
+        &self,
+        items: &[TItem],
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<(), TError> {
+        self.recorder.record(items).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        testing::{MockAppStates, MockBehavior, MockLogger},
+        RoundTripPusher, RpcAggregator,
+    };
+
+    use super::MockCallback;
+
+    #[cfg(feature = "with-telemetry")]
+    fn create_telemetry() -> my_telemetry::MyTelemetryContext {
+        my_telemetry::MyTelemetryCompiler::new().compile()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panic_is_retried_after_retry_delay() {
+        let pusher = RoundTripPusher::new(
+            "test".to_string(),
+            10,
+            Arc::new(MockAppStates::new()),
+            Arc::new(MockLogger::new()),
+        );
+
+        let callback =
+            Arc::new(MockCallback::<u32>::new().then(MockBehavior::Panic("boom".to_string())));
+        pusher.start(callback.clone()).await;
+
+        let started = tokio::time::Instant::now();
+        pusher.publish_many(vec![1, 2, 3].into_iter()).await;
+        callback.wait_for_calls(2).await;
+
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(callback.get_batches(), vec![vec![1, 2, 3], vec![1, 2, 3]]);
+        assert_eq!(pusher.get_metrics().panics, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hanging_callback_times_out() {
+        let logger = Arc::new(MockLogger::new());
+        let pusher = RoundTripPusher::new(
+            "test".to_string(),
+            10,
+            Arc::new(MockAppStates::new()),
+            logger.clone(),
+        );
+
+        let callback = Arc::new(MockCallback::<u32>::new().then(MockBehavior::Hang));
+        pusher.start(callback.clone()).await;
+
+        let started = tokio::time::Instant::now();
+        pusher.publish(1).await;
+        callback.wait_for_calls(2).await;
+
+        assert!(started.elapsed() >= Duration::from_secs(11));
+        assert_eq!(pusher.get_metrics().timeouts, 1);
+        assert_eq!(
+            logger.get_fatal_errors(),
+            vec!["round trip pusher test: Attempt 1 timeout".to_string()]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_linger_collects_items_into_one_batch() {
+        let pusher = RoundTripPusher::builder(
+            "test".to_string(),
+            Arc::new(MockAppStates::new()),
+            Arc::new(MockLogger::new()),
+        )
+        .max_amount_per_round_trip(10)
+        .linger(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+        let callback = Arc::new(MockCallback::<u32>::new());
+        pusher.start(callback.clone()).await;
+
+        pusher.publish(1).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        pusher.publish(2).await;
+        callback.wait_for_calls(1).await;
+
+        assert_eq!(callback.get_batches(), vec![vec![1, 2]]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failure_is_returned_to_the_caller() {
+        let aggregator = RpcAggregator::new(
+            "test".to_string(),
+            10,
+            Arc::new(MockAppStates::new()),
+            Arc::new(MockLogger::new()),
+        );
+
+        let callback = Arc::new(
+            MockCallback::<u32, String>::new().then(MockBehavior::Fail("failed".to_string())),
+        );
+        aggregator.start(callback.clone()).await;
+
+        let result = aggregator
+            .execute_request(
+                1,
+                #[cfg(feature = "with-telemetry")]
+                create_telemetry(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().as_str(), "failed");
+
+        let result = aggregator
+            .execute_request(
+                2,
+                #[cfg(feature = "with-telemetry")]
+                create_telemetry(),
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(callback.get_calls_count(), 2);
+    }
+}
@@ -0,0 +1,63 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use rust_extensions::Logger;
+
+pub struct MockLogger {
+    fatal_errors: Mutex<Vec<String>>,
+}
+
+impl MockLogger {
+    pub fn new() -> Self {
+        Self {
+            fatal_errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn get_fatal_errors(&self) -> Vec<String> {
+        self.fatal_errors.lock().unwrap().clone()
+    }
+}
+
+impl Default for MockLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Logger for MockLogger {
+    fn write_info(
+        &self,
+        _process: String,
+        _message: String,
+        _ctx: Option<HashMap<String, String>>,
+    ) {
+    }
+
+    fn write_warning(
+        &self,
+        _process: String,
+        _message: String,
+        _ctx: Option<HashMap<String, String>>,
+    ) {
+    }
+
+    fn write_error(
+        &self,
+        _process: String,
+        _message: String,
+        _ctx: Option<HashMap<String, String>>,
+    ) {
+    }
+
+    fn write_fatal_error(
+        &self,
+        process: String,
+        message: String,
+        _ctx: Option<HashMap<String, String>>,
+    ) {
+        self.fatal_errors
+            .lock()
+            .unwrap()
+            .push(format!("{}: {}", process, message));
+    }
+}
@@ -0,0 +1,88 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use tokio::sync::Notify;
+
+use super::MockBehavior;
+
+// Records every call of a mock callback and hands out the scripted behaviors in order.
+// Closures passed to start_with_lease_fn can record through it directly.
+pub struct MockRecorder<TItem, TError = String> {
+    batches: Mutex<Vec<Vec<TItem>>>,
+    script: Mutex<VecDeque<MockBehavior<TError>>>,
+    called: Notify,
+}
+
+impl<TItem: Clone, TError> MockRecorder<TItem, TError> {
+    pub fn new() -> Self {
+        Self {
+            batches: Mutex::new(Vec::new()),
+            script: Mutex::new(VecDeque::new()),
+            called: Notify::new(),
+        }
+    }
+
+    pub fn push_behavior(&self, behavior: MockBehavior<TError>) {
+        self.script.lock().unwrap().push_back(behavior);
+    }
+
+    pub fn get_batches(&self) -> Vec<Vec<TItem>> {
+        self.batches.lock().unwrap().clone()
+    }
+
+    pub fn get_items(&self) -> Vec<TItem> {
+        self.get_batches().into_iter().flatten().collect()
+    }
+
+    pub fn get_calls_count(&self) -> usize {
+        self.batches.lock().unwrap().len()
+    }
+
+    pub async fn wait_for_calls(&self, amount: usize) {
+        loop {
+            let called = self.called.notified();
+
+            if self.get_calls_count() >= amount {
+                return;
+            }
+
+            called.await;
+        }
+    }
+
+    pub async fn wait_for_items(&self, amount: usize) {
+        loop {
+            let called = self.called.notified();
+
+            if self.get_items().len() >= amount {
+                return;
+            }
+
+            called.await;
+        }
+    }
+
+    // Returns Err if the call has to fail with the scripted error
+    pub async fn record(&self, items: &[TItem]) -> Result<(), TError> {
+        self.batches.lock().unwrap().push(items.to_vec());
+        self.called.notify_waiters();
+
+        let behavior = self.script.lock().unwrap().pop_front();
+
+        match behavior {
+            None | Some(MockBehavior::Succeed) => Ok(()),
+            Some(MockBehavior::Fail(err)) => Err(err),
+            Some(MockBehavior::Panic(message)) => panic!("{}", message),
+            Some(MockBehavior::Hang) => std::future::pending().await,
+            Some(MockBehavior::Delay(duration)) => {
+                tokio::time::sleep(duration).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<TItem: Clone, TError> Default for MockRecorder<TItem, TError> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
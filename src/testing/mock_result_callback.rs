@@ -0,0 +1,95 @@
+use crate::RpcAggregatorWithResultCallback;
+
+use super::{MockBehavior, MockRecorder};
+
+// Same as MockCallback, but successful calls map every item into a result
+pub struct MockResultCallback<TItem, TResult, TError = String> {
+    recorder: MockRecorder<TItem, TError>,
+    map: Box<dyn Fn(&TItem) -> TResult + Send + Sync + 'static>,
+}
+
+impl<TItem: Clone, TResult, TError> MockResultCallback<TItem, TResult, TError> {
+    pub fn new(map: impl Fn(&TItem) -> TResult + Send + Sync + 'static) -> Self {
+        Self {
+            recorder: MockRecorder::new(),
+            map: Box::new(map),
+        }
+    }
+
+    pub fn then(self, behavior: MockBehavior<TError>) -> Self {
+        self.recorder.push_behavior(behavior);
+        self
+    }
+
+    pub fn push_behavior(&self, behavior: MockBehavior<TError>) {
+        self.recorder.push_behavior(behavior);
+    }
+
+    pub fn get_batches(&self) -> Vec<Vec<TItem>> {
+        self.recorder.get_batches()
+    }
+
+    pub fn get_calls_count(&self) -> usize {
+        self.recorder.get_calls_count()
+    }
+
+    pub async fn wait_for_calls(&self, amount: usize) {
+        self.recorder.wait_for_calls(amount).await;
+    }
+}
+
+#[async_trait::async_trait]
+impl<TItem: Clone + Send + Sync + 'static, TResult: Send + 'static, TError: Send + 'static>
+    RpcAggregatorWithResultCallback<TItem, TResult, TError>
+    for MockResultCallback<TItem, TResult, TError>
+{
+    async fn handle(
+        &self,
+        items: &[TItem],
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<Vec<TResult>, TError> {
+        self.recorder.record(items).await?;
+        Ok(items.iter().map(|item| (self.map)(item)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        testing::{MockAppStates, MockBehavior, MockLogger},
+        RpcAggregatorWithResult,
+    };
+
+    use super::MockResultCallback;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_results_are_mapped_after_delay() {
+        let aggregator = RpcAggregatorWithResult::new(
+            "test".to_string(),
+            10,
+            Arc::new(MockAppStates::new()),
+            Arc::new(MockLogger::new()),
+        );
+
+        let callback = Arc::new(
+            MockResultCallback::<u32, u32, String>::new(|item| item * 2)
+                .then(MockBehavior::Delay(Duration::from_secs(3))),
+        );
+        aggregator.start(callback.clone()).await;
+
+        let started = tokio::time::Instant::now();
+        let result = aggregator
+            .execute_multi_requests(
+                vec![1, 2, 3],
+                #[cfg(feature = "with-telemetry")]
+                my_telemetry::MyTelemetryCompiler::new().compile(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), vec![2, 4, 6]);
+        assert!(started.elapsed() >= Duration::from_secs(3));
+        assert_eq!(callback.get_batches(), vec![vec![1, 2, 3]]);
+    }
+}
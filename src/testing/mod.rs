@@ -0,0 +1,13 @@
+mod mock_app_states;
+mod mock_behavior;
+mod mock_callback;
+mod mock_logger;
+mod mock_recorder;
+mod mock_result_callback;
+
+pub use mock_app_states::*;
+pub use mock_behavior::*;
+pub use mock_callback::*;
+pub use mock_logger::*;
+pub use mock_recorder::*;
+pub use mock_result_callback::*;
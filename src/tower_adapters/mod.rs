@@ -0,0 +1,6 @@
+mod rpc_aggregator_layer;
+mod rpc_aggregator_service;
+mod service_batch_callback;
+
+pub use rpc_aggregator_layer::*;
+pub use rpc_aggregator_service::*;
@@ -0,0 +1,83 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use rust_extensions::{ApplicationStates, Logger};
+
+use crate::{AggregatorSettings, AggregatorSettingsError, RpcAggregatorWithResult};
+
+use super::{service_batch_callback::ServiceBatchCallback, RpcAggregatorService};
+
+// Wraps a Service<Vec<Req>> into a Service<Req> backed by RpcAggregatorWithResult.
+// Every layer() call starts its own aggregator for the inner service it gets, so it has
+// to happen inside a tokio runtime. Clones of the returned service share that aggregator.
+pub struct RpcAggregatorLayer<
+    TRequest: Send + 'static,
+    TResponse: Send + 'static,
+    TError: Send + Sync + 'static,
+> {
+    name: String,
+    settings: AggregatorSettings,
+    app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+    logger: Arc<dyn Logger + Send + Sync + 'static>,
+    #[cfg(feature = "with-telemetry")]
+    my_telemetry: my_telemetry::MyTelemetryContext,
+    request: PhantomData<fn(TRequest) -> TResponse>,
+    error: PhantomData<fn() -> TError>,
+}
+
+impl<TRequest: Send + 'static, TResponse: Send + 'static, TError: Send + Sync + 'static>
+    RpcAggregatorLayer<TRequest, TResponse, TError>
+{
+    pub fn new(
+        name: String,
+        settings: AggregatorSettings,
+        app_states: Arc<dyn ApplicationStates + Send + Sync + 'static>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Result<Self, AggregatorSettingsError> {
+        settings.validate(&name)?;
+
+        Ok(Self {
+            name,
+            settings,
+            app_states,
+            logger,
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+            request: PhantomData,
+            error: PhantomData,
+        })
+    }
+}
+
+impl<TRequest, TResponse, TError, TService> tower_layer::Layer<TService>
+    for RpcAggregatorLayer<TRequest, TResponse, TError>
+where
+    TRequest: Send + 'static,
+    TResponse: Send + 'static,
+    TError: Send + Sync + 'static,
+    TService: tower_service::Service<Vec<TRequest>, Response = Vec<TResponse>, Error = TError>
+        + Clone
+        + Send
+        + 'static,
+    TService::Future: Send + 'static,
+{
+    type Service = RpcAggregatorService<TRequest, TResponse, TError>;
+
+    fn layer(&self, inner: TService) -> Self::Service {
+        let aggregator = RpcAggregatorWithResult::from_settings(
+            self.name.clone(),
+            self.settings.clone(),
+            self.app_states.clone(),
+            self.logger.clone(),
+        )
+        .expect("Settings are validated by RpcAggregatorLayer::new");
+
+        aggregator.start_owned_sync(Arc::new(ServiceBatchCallback::new(inner)));
+
+        RpcAggregatorService::new(
+            Arc::new(aggregator),
+            #[cfg(feature = "with-telemetry")]
+            self.my_telemetry.clone(),
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::{batch_engine::CapacityReservation, RpcAggregatorWithResult};
+
+// poll_ready reserves a slot of the aggregator queue capacity, and call() uses it,
+// so the service reports not ready while the queue is full.
+pub struct RpcAggregatorService<
+    TRequest: Send + 'static,
+    TResponse: Send + 'static,
+    TError: Send + Sync + 'static,
+> {
+    aggregator: Arc<RpcAggregatorWithResult<TRequest, TResponse, TError>>,
+    reservation: CapacityReservation,
+    #[cfg(feature = "with-telemetry")]
+    my_telemetry: my_telemetry::MyTelemetryContext,
+}
+
+impl<TRequest: Send + 'static, TResponse: Send + 'static, TError: Send + Sync + 'static>
+    RpcAggregatorService<TRequest, TResponse, TError>
+{
+    pub fn new(
+        aggregator: Arc<RpcAggregatorWithResult<TRequest, TResponse, TError>>,
+        #[cfg(feature = "with-telemetry")] my_telemetry: my_telemetry::MyTelemetryContext,
+    ) -> Self {
+        Self {
+            reservation: CapacityReservation::new(aggregator.get_capacity()),
+            aggregator,
+            #[cfg(feature = "with-telemetry")]
+            my_telemetry,
+        }
+    }
+}
+
+impl<TRequest: Send + 'static, TResponse: Send + 'static, TError: Send + Sync + 'static> Clone
+    for RpcAggregatorService<TRequest, TResponse, TError>
+{
+    fn clone(&self) -> Self {
+        Self::new(
+            self.aggregator.clone(),
+            #[cfg(feature = "with-telemetry")]
+            self.my_telemetry.clone(),
+        )
+    }
+}
+
+impl<TRequest: Send + 'static, TResponse: Send + 'static, TError: Send + Sync + 'static>
+    tower_service::Service<TRequest> for RpcAggregatorService<TRequest, TResponse, TError>
+{
+    type Response = TResponse;
+    type Error = Arc<TError>;
+    type Future = Pin<Box<dyn Future<Output = Result<TResponse, Arc<TError>>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.reservation.poll_reserve(cx).map(Ok)
+    }
+
+    fn call(&mut self, request: TRequest) -> Self::Future {
+        let aggregator = self.aggregator.clone();
+        let permit = self.reservation.take_permit();
+        #[cfg(feature = "with-telemetry")]
+        let my_telemetry = self.my_telemetry.clone();
+
+        Box::pin(async move {
+            aggregator
+                .execute_reserved(
+                    request,
+                    permit,
+                    #[cfg(feature = "with-telemetry")]
+                    my_telemetry,
+                )
+                .await
+        })
+    }
+}
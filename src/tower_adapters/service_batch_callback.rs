@@ -0,0 +1,47 @@
+use std::sync::Mutex;
+
+use crate::{OwnedCallbackError, RpcAggregatorWithResultOwnedCallback};
+
+// Calls the inner batch service. The service is cloned per batch the same way tower
+// middlewares do, so the callback does not have to hold a lock across the call.
+pub struct ServiceBatchCallback<TService> {
+    service: Mutex<TService>,
+}
+
+impl<TService> ServiceBatchCallback<TService> {
+    pub fn new(service: TService) -> Self {
+        Self {
+            service: Mutex::new(service),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<TRequest, TResponse, TService>
+    RpcAggregatorWithResultOwnedCallback<TRequest, TResponse, TService::Error>
+    for ServiceBatchCallback<TService>
+where
+    TRequest: Send + 'static,
+    TResponse: Send + 'static,
+    TService:
+        tower_service::Service<Vec<TRequest>, Response = Vec<TResponse>> + Clone + Send + 'static,
+    TService::Error: Send + Sync + 'static,
+    TService::Future: Send + 'static,
+{
+    async fn handle(
+        &self,
+        items: Vec<TRequest>,
+        #[cfg(feature = "with-telemetry")] _my_telemetry: &my_telemetry::MyTelemetryContext,
+    ) -> Result<Vec<TResponse>, OwnedCallbackError<TRequest, TService::Error>> {
+        let mut service = self.service.lock().unwrap().clone();
+
+        if let Err(err) = std::future::poll_fn(|cx| service.poll_ready(cx)).await {
+            return Err(OwnedCallbackError::Failed(err));
+        }
+
+        service
+            .call(items)
+            .await
+            .map_err(OwnedCallbackError::Failed)
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::{Arc, Mutex, Weak};
+
+// Keeps weak references only, so dropped aggregators disappear from the registry.
+// Items are upgraded under the lock and used after it is released.
+pub(crate) struct WeakRegistry<T: ?Sized> {
+    items: Mutex<Vec<RegisteredItem<T>>>,
+}
+
+struct RegisteredItem<T: ?Sized> {
+    name: String,
+    // Live items with the same name get different instance numbers, starting with 0
+    instance_no: usize,
+    item: Weak<T>,
+}
+
+impl<T: ?Sized> WeakRegistry<T> {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, name: &str, item: &Arc<T>) {
+        let mut items = self.items.lock().unwrap();
+        items.retain(|item| item.item.strong_count() > 0);
+
+        let instance_no = (0..)
+            .find(|instance_no| {
+                !items
+                    .iter()
+                    .any(|item| item.name == name && item.instance_no == *instance_no)
+            })
+            .unwrap();
+
+        items.push(RegisteredItem {
+            name: name.to_string(),
+            instance_no,
+            item: Arc::downgrade(item),
+        });
+    }
+
+    pub fn get_items(&self) -> Vec<Arc<T>> {
+        self.get_instances()
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    pub fn get_instances(&self) -> Vec<(usize, Arc<T>)> {
+        let mut items = self.items.lock().unwrap();
+        items.retain(|item| item.item.strong_count() > 0);
+        items
+            .iter()
+            .filter_map(|item| Some((item.instance_no, item.item.upgrade()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::WeakRegistry;
+
+    #[test]
+    fn test_dropped_items_are_removed() {
+        let registry = WeakRegistry::new();
+        let first = Arc::new(1);
+        let second = Arc::new(2);
+        registry.register("first", &first);
+        registry.register("second", &second);
+
+        drop(first);
+
+        let items: Vec<i32> = registry.get_items().into_iter().map(|item| *item).collect();
+        assert_eq!(items, vec![2]);
+    }
+
+    #[test]
+    fn test_same_names_get_free_instance_numbers() {
+        let registry = WeakRegistry::new();
+        let first = Arc::new(1);
+        let second = Arc::new(2);
+        let other = Arc::new(3);
+        registry.register("test", &first);
+        registry.register("test", &second);
+        registry.register("other", &other);
+
+        drop(first);
+        let third = Arc::new(4);
+        registry.register("test", &third);
+
+        let instances: Vec<(usize, i32)> = registry
+            .get_instances()
+            .into_iter()
+            .map(|(instance_no, item)| (instance_no, *item))
+            .collect();
+        assert_eq!(instances, vec![(1, 2), (0, 3), (0, 4)]);
+    }
+}
@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+use std::{sync::Arc, time::Duration};
+
+use rpc_aggregator::{
+    testing::{MockAppStates, MockLogger, MockRecorder},
+    BatchTicket, RpcAggregator, RpcAggregatorWithResult,
+};
+
+pub fn create_app_states() -> Arc<MockAppStates> {
+    Arc::new(MockAppStates::new())
+}
+
+pub fn create_logger() -> Arc<MockLogger> {
+    Arc::new(MockLogger::new())
+}
+
+pub fn create_recorder<TItem: Clone>() -> Arc<MockRecorder<TItem>> {
+    Arc::new(MockRecorder::new())
+}
+
+pub async fn wait_until(condition: impl Fn() -> bool) {
+    let waiting = async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    };
+
+    if tokio::time::timeout(Duration::from_secs(60), waiting)
+        .await
+        .is_err()
+    {
+        panic!("Condition is not met in 60 seconds");
+    }
+}
+
+#[cfg(feature = "with-telemetry")]
+pub fn create_telemetry() -> my_telemetry::MyTelemetryContext {
+    my_telemetry::MyTelemetryCompiler::new().compile()
+}
+
+pub async fn execute_request<TItem: Send + 'static, TError: Send + Sync + 'static>(
+    aggregator: &RpcAggregator<TItem, TError>,
+    item: TItem,
+) -> Result<(), Arc<TError>> {
+    aggregator
+        .execute_request(
+            item,
+            #[cfg(feature = "with-telemetry")]
+            create_telemetry(),
+        )
+        .await
+}
+
+pub async fn submit<TItem: Send + 'static, TError: Send + Sync + 'static>(
+    aggregator: &RpcAggregator<TItem, TError>,
+    item: TItem,
+) -> BatchTicket<TError> {
+    aggregator
+        .submit(
+            item,
+            #[cfg(feature = "with-telemetry")]
+            create_telemetry(),
+        )
+        .await
+}
+
+pub async fn execute_request_with_result<
+    TItem: Send + 'static,
+    TResult: Send + 'static,
+    TError: Send + Sync + 'static,
+>(
+    aggregator: &RpcAggregatorWithResult<TItem, TResult, TError>,
+    item: TItem,
+) -> Result<TResult, Arc<TError>> {
+    aggregator
+        .execute_request(
+            item,
+            #[cfg(feature = "with-telemetry")]
+            create_telemetry(),
+        )
+        .await
+}
+
+pub async fn execute_multi_requests_with_result<
+    TItem: Send + 'static,
+    TResult: Send + 'static,
+    TError: Send + Sync + 'static,
+>(
+    aggregator: &RpcAggregatorWithResult<TItem, TResult, TError>,
+    items: Vec<TItem>,
+) -> Result<Vec<TResult>, Arc<TError>> {
+    aggregator
+        .execute_multi_requests(
+            items,
+            #[cfg(feature = "with-telemetry")]
+            create_telemetry(),
+        )
+        .await
+}
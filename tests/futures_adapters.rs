@@ -0,0 +1,80 @@
+#![cfg(feature = "with-futures")]
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{create_app_states, create_logger, create_recorder};
+use futures::{future::poll_fn, SinkExt, StreamExt};
+use rpc_aggregator::{
+    ItemsLease, RoundTripPusher, RoundTripPusherSink, RpcAggregatorStreamExt,
+    RpcAggregatorWithResult,
+};
+
+#[tokio::test]
+async fn test_sink_is_not_ready_while_queue_is_full() {
+    let pusher = Arc::new(
+        RoundTripPusher::builder("test".to_string(), create_app_states(), create_logger())
+            .queue_capacity(1)
+            .build()
+            .unwrap(),
+    );
+
+    let mut sink = RoundTripPusherSink::new(pusher.clone());
+    sink.send(1).await.unwrap();
+
+    let mut ready = poll_fn(|cx| sink.poll_ready_unpin(cx));
+    assert!(futures::poll!(&mut ready).is_pending());
+
+    let recorder = create_recorder();
+    let callback_recorder = recorder.clone();
+    pusher
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                recorder.record(&items).await.unwrap();
+            }
+        })
+        .await;
+
+    tokio::time::timeout(Duration::from_secs(5), ready)
+        .await
+        .unwrap()
+        .unwrap();
+    sink.send(2).await.unwrap();
+
+    recorder.wait_for_items(2).await;
+    assert_eq!(recorder.get_items(), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_zero_max_in_flight_is_treated_as_one() {
+    let aggregator = Arc::new(RpcAggregatorWithResult::<u32, u32, String>::new(
+        "test".to_string(),
+        4,
+        create_app_states(),
+        create_logger(),
+    ));
+    aggregator
+        .start_with_lease_fn(|items: ItemsLease<u32>| async move {
+            Ok(items.iter().map(|item| item * 10).collect())
+        })
+        .await;
+
+    let results = futures::stream::iter(0..3u32).execute_with(
+        aggregator,
+        0,
+        #[cfg(feature = "with-telemetry")]
+        common::create_telemetry(),
+    );
+
+    let results: Vec<u32> =
+        tokio::time::timeout(Duration::from_secs(5), results.collect::<Vec<_>>())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect();
+
+    assert_eq!(results, vec![0, 10, 20]);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    create_app_states, create_logger, create_recorder, execute_multi_requests_with_result,
+};
+use proptest::prelude::*;
+use rpc_aggregator::{ItemsLease, RoundTripPusher, RpcAggregatorWithResult};
+
+fn run<TFuture: std::future::Future>(future: TFuture) -> TFuture::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_pusher_keeps_order_and_max_amount(
+        items in prop::collection::vec(any::<u16>(), 1..200),
+        max_amount in 1usize..20,
+    ) {
+        let batches = run(async {
+            let pusher = RoundTripPusher::new(
+                "test".to_string(),
+                max_amount,
+                create_app_states(),
+                create_logger(),
+            );
+            pusher.publish_many(items.clone().into_iter()).await;
+
+            let recorder = create_recorder();
+            let callback_recorder = recorder.clone();
+            pusher
+                .start_with_lease_fn(move |items: ItemsLease<u16>| {
+                    let recorder = callback_recorder.clone();
+                    async move {
+                        recorder.record(&items).await.unwrap();
+                    }
+                })
+                .await;
+
+            recorder.wait_for_items(items.len()).await;
+            recorder.get_batches()
+        });
+
+        prop_assert_eq!(batches.len(), items.len().div_ceil(max_amount));
+        prop_assert!(batches.iter().all(|batch| !batch.is_empty() && batch.len() <= max_amount));
+        prop_assert_eq!(batches.concat(), items);
+    }
+
+    #[test]
+    fn prop_results_are_routed_to_their_callers(
+        requests in prop::collection::vec(prop::collection::vec(any::<u16>(), 1..5), 1..30),
+        max_amount in 1usize..10,
+    ) {
+        let results = run(async {
+            let aggregator = Arc::new(RpcAggregatorWithResult::<u16, u32, String>::new(
+                "test".to_string(),
+                max_amount,
+                create_app_states(),
+                create_logger(),
+            ));
+
+            aggregator
+                .start_with_lease_fn(|items: ItemsLease<u16>| async move {
+                    Ok(items.iter().map(|item| *item as u32 + 1).collect())
+                })
+                .await;
+
+            let mut callers = Vec::new();
+            for request in requests.clone() {
+                let aggregator = aggregator.clone();
+                callers.push(tokio::spawn(async move {
+                    execute_multi_requests_with_result(&aggregator, request).await
+                }));
+            }
+
+            let mut results = Vec::new();
+            for caller in callers {
+                results.push(caller.await.unwrap().unwrap());
+            }
+            results
+        });
+
+        for (request, result) in requests.iter().zip(results) {
+            let expected: Vec<u32> = request.iter().map(|item| *item as u32 + 1).collect();
+            prop_assert_eq!(result, expected);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+mod common;
+
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use common::{create_app_states, create_logger, create_recorder, wait_until};
+use rpc_aggregator::{
+    testing::{MockBehavior, MockRecorder},
+    AggregatorEvents, ItemsLease, RetryPolicy, RoundTripOwnedCallback, RoundTripPusher,
+};
+
+async fn start_recording(pusher: &RoundTripPusher<u32>, recorder: &Arc<MockRecorder<u32>>) {
+    let recorder = recorder.clone();
+    pusher
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = recorder.clone();
+            async move {
+                recorder.record(&items).await.unwrap();
+            }
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_items_are_delivered_in_order_and_split_by_max_amount() {
+    let pusher = RoundTripPusher::new("test".to_string(), 3, create_app_states(), create_logger());
+    pusher.publish_many(0..10).await;
+    assert_eq!(pusher.get_metrics().queue_depth, 10);
+
+    let recorder = create_recorder();
+    start_recording(&pusher, &recorder).await;
+    recorder.wait_for_items(10).await;
+
+    assert_eq!(
+        recorder.get_batches(),
+        vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8], vec![9]]
+    );
+
+    let metrics = pusher.get_metrics();
+    assert_eq!(metrics.items_enqueued, 10);
+    assert_eq!(metrics.batches_sent, 4);
+    wait_until(|| pusher.get_metrics().items_delivered == 10).await;
+    assert_eq!(pusher.get_metrics().queue_depth, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_panicking_batch_is_dropped_after_max_attempts() {
+    let logger = create_logger();
+    let pusher = RoundTripPusher::new("test".to_string(), 10, create_app_states(), logger.clone());
+
+    let recorder = create_recorder();
+    let callback_recorder = recorder.clone();
+    pusher
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                recorder.record(&items).await.unwrap();
+                if items.contains(&13) {
+                    panic!("Bad item");
+                }
+            }
+        })
+        .await;
+
+    pusher.publish(13).await;
+    wait_until(|| pusher.get_metrics().items_dropped == 1).await;
+
+    assert_eq!(recorder.get_calls_count(), 5);
+    assert_eq!(pusher.get_metrics().panics, 5);
+    assert_eq!(
+        logger.get_fatal_errors().last().unwrap(),
+        "round trip pusher test: Attempt 5. Skipping items"
+    );
+
+    pusher.publish(1).await;
+    recorder.wait_for_calls(6).await;
+    assert_eq!(recorder.get_batches().last().unwrap(), &vec![1]);
+}
+
+// Listeners are called from the read loop, so a panicking listener takes the loop down
+struct PanicOnFirstBatch {
+    panicked: AtomicBool,
+}
+
+impl AggregatorEvents for PanicOnFirstBatch {
+    fn on_batch_started(&self, _aggregator_name: &str, _batch_size: usize, _attempt_no: usize) {
+        if !self.panicked.swap(true, Ordering::SeqCst) {
+            panic!("Bad listener");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_read_loop_is_restarted_after_panic() {
+    let logger = create_logger();
+    let mut pusher =
+        RoundTripPusher::new("test".to_string(), 1, create_app_states(), logger.clone());
+    pusher.register_events(Arc::new(PanicOnFirstBatch {
+        panicked: AtomicBool::new(false),
+    }));
+    assert!(!pusher.is_running());
+
+    pusher.publish_many(1..4).await;
+
+    let recorder = create_recorder();
+    start_recording(&pusher, &recorder).await;
+    assert!(pusher.is_running());
+
+    recorder.wait_for_items(2).await;
+
+    // The batch in flight is lost, the rest of the queue survives the restart
+    assert_eq!(recorder.get_batches(), vec![vec![2], vec![3]]);
+    assert_eq!(pusher.get_metrics().loop_restarts, 1);
+    assert!(logger.get_fatal_errors()[0]
+        .starts_with("round trip pusher test: Read loop panic. Restarting."));
+    assert!(pusher.is_running());
+
+    let status = pusher.status();
+    assert!(status.running);
+    assert!(status.in_flight.is_none());
+    assert!(status.last_error.unwrap().message.contains("Bad listener"));
+}
+
+#[tokio::test]
+#[should_panic(expected = "after it is started")]
+async fn test_events_can_not_be_registered_after_start() {
+    let mut pusher =
+        RoundTripPusher::new("test".to_string(), 1, create_app_states(), create_logger());
+    start_recording(&pusher, &create_recorder()).await;
+
+    pusher.register_events(Arc::new(PanicOnFirstBatch {
+        panicked: AtomicBool::new(false),
+    }));
+}
+
+// Neither Clone nor Sync, so the items can only reach a retry through the lease
+struct Payload(std::cell::Cell<u32>);
+
+#[tokio::test(start_paused = true)]
+async fn test_closure_gets_the_same_items_after_panic() {
+    let pusher = RoundTripPusher::new("test".to_string(), 10, create_app_states(), create_logger());
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Panic("First attempt".to_string()));
+    let callback_recorder = recorder.clone();
+    pusher
+        .start_with_lease_fn(move |items: ItemsLease<Payload>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                let values: Vec<u32> = items.iter().map(|item| item.0.get()).collect();
+                recorder.record(&values).await.unwrap();
+            }
+        })
+        .await;
+
+    pusher
+        .publish_many((1..4).map(|value| Payload(std::cell::Cell::new(value))))
+        .await;
+    recorder.wait_for_calls(2).await;
+
+    assert_eq!(recorder.get_batches(), vec![vec![1, 2, 3], vec![1, 2, 3]]);
+    assert_eq!(pusher.get_metrics().retries, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timed_out_batch_is_retried() {
+    let pusher = RoundTripPusher::builder("test".to_string(), create_app_states(), create_logger())
+        .tick_timeout(Duration::from_secs(3))
+        .retry_policy(RetryPolicy::new(2, Duration::from_millis(100)))
+        .build()
+        .unwrap();
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Hang);
+    start_recording(&pusher, &recorder).await;
+
+    let started = tokio::time::Instant::now();
+    pusher.publish(1).await;
+    wait_until(|| pusher.get_metrics().items_delivered == 1).await;
+
+    assert!(started.elapsed() >= Duration::from_millis(3100));
+    assert_eq!(recorder.get_batches(), vec![vec![1], vec![1]]);
+
+    let metrics = pusher.get_metrics();
+    assert_eq!(metrics.timeouts, 1);
+    assert_eq!(metrics.aborted_attempts, 1);
+    assert_eq!(metrics.items_dropped, 0);
+}
+
+struct PanicOwned;
+
+#[async_trait::async_trait]
+impl RoundTripOwnedCallback<u32> for PanicOwned {
+    async fn handle(&self, _items: Vec<u32>) -> Result<(), Vec<u32>> {
+        panic!("Items are gone");
+    }
+}
+
+#[derive(Default)]
+struct DroppedItems {
+    amount: std::sync::atomic::AtomicUsize,
+}
+
+impl AggregatorEvents for DroppedItems {
+    fn on_items_dropped(&self, _aggregator_name: &str, amount: usize) {
+        self.amount.fetch_add(amount, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_owned_batch_is_dropped_without_waiting_for_retry() {
+    let logger = create_logger();
+    let dropped = Arc::new(DroppedItems::default());
+    let pusher = RoundTripPusher::builder("test".to_string(), create_app_states(), logger.clone())
+        .retry_policy(RetryPolicy::new(5, Duration::from_secs(60)))
+        .events(dropped.clone())
+        .build()
+        .unwrap();
+    pusher.start_owned(Arc::new(PanicOwned)).await;
+
+    let started = tokio::time::Instant::now();
+    pusher.publish_many(1..3).await;
+    wait_until(|| pusher.get_metrics().items_dropped == 2).await;
+
+    assert!(started.elapsed() < Duration::from_secs(60));
+    assert_eq!(dropped.amount.load(Ordering::SeqCst), 2);
+
+    let metrics = pusher.get_metrics();
+    assert_eq!(metrics.panics, 1);
+    assert_eq!(metrics.retries, 0);
+    assert_eq!(
+        logger.get_fatal_errors().last().unwrap(),
+        "round trip pusher test: Attempt 1. Items were consumed by the callback. Skipping items"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_owned_closure_hands_items_back_for_retry() {
+    let pusher = RoundTripPusher::builder("test".to_string(), create_app_states(), create_logger())
+        .retry_policy(RetryPolicy::new(3, Duration::from_millis(100)))
+        .build()
+        .unwrap();
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Fail("Busy".to_string()));
+    let callback_recorder = recorder.clone();
+    pusher
+        .start_with_fn(move |items: Vec<u32>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                match recorder.record(&items).await {
+                    Ok(()) => Ok(()),
+                    Err(_) => Err(items),
+                }
+            }
+        })
+        .await;
+
+    pusher.publish_many(1..3).await;
+    wait_until(|| pusher.get_metrics().items_delivered == 2).await;
+
+    assert_eq!(recorder.get_batches(), vec![vec![1, 2], vec![1, 2]]);
+    assert_eq!(pusher.get_metrics().retries, 1);
+}
+
+#[tokio::test]
+async fn test_slice_closure_is_a_callback() {
+    let pusher = RoundTripPusher::new("test".to_string(), 10, create_app_states(), create_logger());
+
+    let total = Arc::new(AtomicU32::new(0));
+    let callback_total = total.clone();
+    pusher
+        .start(Arc::new(move |items: &[u32]| {
+            callback_total.fetch_add(items.iter().sum(), Ordering::SeqCst);
+            async {}
+        }))
+        .await;
+
+    pusher.publish_many(1..5).await;
+    wait_until(|| pusher.get_metrics().items_delivered == 4).await;
+
+    assert_eq!(total.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_producers_deliver_every_item() {
+    let pusher = Arc::new(RoundTripPusher::new(
+        "test".to_string(),
+        50,
+        create_app_states(),
+        create_logger(),
+    ));
+
+    let recorder = create_recorder();
+    start_recording(&pusher, &recorder).await;
+
+    let mut producers = Vec::new();
+    for producer_no in 0..8u32 {
+        let pusher = pusher.clone();
+        producers.push(tokio::spawn(async move {
+            for item in 0..500u32 {
+                pusher.publish(producer_no * 1000 + item).await;
+            }
+        }));
+    }
+
+    for producer in producers {
+        producer.await.unwrap();
+    }
+
+    recorder.wait_for_items(4000).await;
+
+    let batches = recorder.get_batches();
+    assert!(batches.iter().all(|batch| batch.len() <= 50));
+
+    let items = recorder.get_items();
+    let unique: BTreeSet<u32> = items.iter().copied().collect();
+    assert_eq!(items.len(), 4000);
+    assert_eq!(unique.len(), 4000);
+
+    // Items of one producer keep their order
+    for producer_no in 0..8u32 {
+        let produced: Vec<u32> = items
+            .iter()
+            .copied()
+            .filter(|item| item / 1000 == producer_no)
+            .collect();
+        let expected: Vec<u32> = (0..500).map(|item| producer_no * 1000 + item).collect();
+        assert_eq!(produced, expected);
+    }
+
+    assert_eq!(pusher.get_count(), 0);
+}
@@ -0,0 +1,269 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    create_app_states, create_logger, create_recorder, execute_request, submit, wait_until,
+};
+use rpc_aggregator::{
+    testing::{MockBehavior, MockRecorder},
+    BatchTicketStatus, HealthRules, HealthStatus, ItemsLease, RetryPolicy, RpcAggregator,
+};
+
+async fn start_failing_on(
+    aggregator: &RpcAggregator<u32, String>,
+    recorder: &Arc<MockRecorder<u32>>,
+    bad_item: u32,
+) {
+    let recorder = recorder.clone();
+    aggregator
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = recorder.clone();
+            async move {
+                recorder.record(&items).await?;
+                if items.contains(&bad_item) {
+                    Err(format!("Bad item {}", bad_item))
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_error_is_fanned_out_to_every_caller_of_the_batch() {
+    let aggregator = Arc::new(
+        RpcAggregator::builder("test".to_string(), create_app_states(), create_logger())
+            .max_amount_per_round_trip(10)
+            .linger(Duration::from_millis(100))
+            .build()
+            .unwrap(),
+    );
+
+    let recorder = create_recorder();
+    start_failing_on(&aggregator, &recorder, 13).await;
+
+    let mut callers = Vec::new();
+    for item in [1, 2, 13, 4, 5] {
+        let aggregator = aggregator.clone();
+        callers.push(tokio::spawn(async move {
+            execute_request(&aggregator, item).await
+        }));
+    }
+
+    let mut errors = Vec::new();
+    for caller in callers {
+        errors.push(caller.await.unwrap().unwrap_err());
+    }
+
+    assert_eq!(recorder.get_batches(), vec![vec![1, 2, 13, 4, 5]]);
+    assert!(errors.iter().all(|err| err.as_str() == "Bad item 13"));
+    assert!(errors.iter().all(|err| Arc::ptr_eq(err, &errors[0])));
+    assert_eq!(aggregator.get_metrics().batches_failed, 1);
+}
+
+#[tokio::test]
+async fn test_health_follows_consecutive_failures() {
+    let aggregator = RpcAggregator::builder(
+        "health_test".to_string(),
+        create_app_states(),
+        create_logger(),
+    )
+    .health_rules(HealthRules::new(None, Some(2), HealthStatus::Unhealthy))
+    .build()
+    .unwrap();
+
+    let health = aggregator.health();
+    assert_eq!(health.status, HealthStatus::Degraded);
+    assert_eq!(
+        health.reasons,
+        vec!["Aggregator is not started".to_string()]
+    );
+
+    let recorder = create_recorder();
+    start_failing_on(&aggregator, &recorder, 13).await;
+    assert!(aggregator.health().is_healthy());
+
+    execute_request(&aggregator, 13).await.unwrap_err();
+    assert!(aggregator.health().is_healthy());
+    execute_request(&aggregator, 13).await.unwrap_err();
+
+    let health = aggregator.health();
+    assert_eq!(health.status, HealthStatus::Unhealthy);
+    assert_eq!(
+        health.reasons,
+        vec!["2 consecutive failures, threshold is 2".to_string()]
+    );
+
+    // Other tests register aggregators in the same process
+    let report = rpc_aggregator::global_health_registry().get_report();
+    let registered = report
+        .aggregators
+        .iter()
+        .find(|health| health.name == "health_test")
+        .unwrap();
+    assert_eq!(registered.status, HealthStatus::Unhealthy);
+    assert_eq!(report.status, HealthStatus::Unhealthy);
+
+    execute_request(&aggregator, 1).await.unwrap();
+    assert!(aggregator.health().is_healthy());
+
+    assert_eq!(aggregator.status().consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn test_status_is_stopped_when_app_is_shutting_down() {
+    let app_states = create_app_states();
+    let aggregator: RpcAggregator<u32, String> =
+        RpcAggregator::new("test".to_string(), 1, app_states.clone(), create_logger());
+
+    aggregator
+        .start_with_lease_fn(|_: ItemsLease<u32>| async { Ok(()) })
+        .await;
+    assert!(!aggregator.status().stopped);
+
+    app_states.set_shutting_down();
+    assert!(aggregator.status().stopped);
+}
+
+#[tokio::test]
+async fn test_successful_and_failed_batches_are_independent() {
+    let aggregator =
+        RpcAggregator::new("test".to_string(), 1, create_app_states(), create_logger());
+
+    let recorder = create_recorder();
+    start_failing_on(&aggregator, &recorder, 13).await;
+
+    let errors = aggregator
+        .execute_multi_requests(
+            vec![1, 13, 3],
+            #[cfg(feature = "with-telemetry")]
+            common::create_telemetry(),
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(errors.keys().copied().collect::<Vec<_>>(), vec![1]);
+    assert_eq!(errors[&1].as_str(), "Bad item 13");
+    assert_eq!(recorder.get_batches(), vec![vec![1], vec![13], vec![3]]);
+
+    assert!(execute_request(&aggregator, 4).await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_callers_panic_when_batch_is_dropped() {
+    let aggregator = Arc::new(RpcAggregator::<u32, String>::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    ));
+
+    let recorder = create_recorder();
+    let callback_recorder = recorder.clone();
+    aggregator
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                recorder.record(&items).await?;
+                panic!("Callback panic");
+            }
+        })
+        .await;
+
+    let caller = {
+        let aggregator = aggregator.clone();
+        tokio::spawn(async move { execute_request(&aggregator, 1).await })
+    };
+
+    assert!(caller.await.unwrap_err().is_panic());
+    assert_eq!(recorder.get_calls_count(), 5);
+
+    let metrics = aggregator.get_metrics();
+    assert_eq!(metrics.panics, 5);
+    assert_eq!(metrics.items_dropped, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_caller_gets_result_of_retried_attempt_after_timeout() {
+    let aggregator = RpcAggregator::<u32, String>::builder(
+        "test".to_string(),
+        create_app_states(),
+        create_logger(),
+    )
+    .tick_timeout(Duration::from_secs(1))
+    .retry_policy(RetryPolicy::new(3, Duration::from_millis(10)))
+    .build()
+    .unwrap();
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Hang);
+    let callback_recorder = recorder.clone();
+    aggregator
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = callback_recorder.clone();
+            async move { recorder.record(&items).await }
+        })
+        .await;
+
+    assert!(execute_request(&aggregator, 1).await.is_ok());
+    assert_eq!(recorder.get_calls_count(), 2);
+
+    wait_until(|| aggregator.get_metrics().items_delivered == 1).await;
+    assert_eq!(aggregator.get_metrics().timeouts, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_ticket_status_and_result_match() {
+    let aggregator =
+        RpcAggregator::builder("test".to_string(), create_app_states(), create_logger())
+            .max_amount_per_round_trip(1)
+            .retry_policy(RetryPolicy::new(1, Duration::from_millis(10)))
+            .build()
+            .unwrap();
+
+    let delivered = submit(&aggregator, 1).await;
+    let failed = submit(&aggregator, 13).await;
+    assert_eq!(delivered.status(), BatchTicketStatus::Pending);
+
+    let recorder = create_recorder();
+    start_failing_on(&aggregator, &recorder, 13).await;
+    wait_until(|| failed.status() != BatchTicketStatus::Pending).await;
+
+    assert_eq!(delivered.status(), BatchTicketStatus::Delivered);
+    assert_eq!(failed.status(), BatchTicketStatus::Failed);
+
+    // Reading the status does not consume the outcome
+    assert!(delivered.await.is_ok());
+    assert_eq!(failed.await.unwrap_err().as_str(), "Bad item 13");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dropped_ticket_reports_panic_message() {
+    let aggregator = RpcAggregator::<u32, String>::builder(
+        "test".to_string(),
+        create_app_states(),
+        create_logger(),
+    )
+    .retry_policy(RetryPolicy::new(1, Duration::from_millis(10)))
+    .build()
+    .unwrap();
+
+    aggregator
+        .start_with_lease_fn(|_items: ItemsLease<u32>| async move {
+            panic!("Callback panic");
+        })
+        .await;
+
+    let ticket = submit(&aggregator, 1).await;
+    wait_until(|| ticket.status() != BatchTicketStatus::Pending).await;
+
+    match ticket.status() {
+        BatchTicketStatus::Dropped(message) => assert!(message.contains("Callback panic")),
+        status => panic!("Unexpected status {:?}", status),
+    }
+
+    let awaiter = tokio::spawn(ticket);
+    assert!(awaiter.await.unwrap_err().is_panic());
+}
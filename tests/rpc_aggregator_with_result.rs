@@ -0,0 +1,270 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    create_app_states, create_logger, create_recorder, execute_multi_requests_with_result,
+    execute_request_with_result, wait_until,
+};
+use rpc_aggregator::{
+    testing::{MockBehavior, MockRecorder},
+    ItemsLease, RetryPolicy, RpcAggregatorWithResult,
+};
+
+async fn start_multiplying(
+    aggregator: &RpcAggregatorWithResult<u32, u32, String>,
+    recorder: &Arc<MockRecorder<u32>>,
+) {
+    let recorder = recorder.clone();
+    aggregator
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = recorder.clone();
+            async move {
+                recorder.record(&items).await?;
+                Ok(items.iter().map(|item| item * 10).collect())
+            }
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_requests_before_start_are_queued() {
+    let aggregator = Arc::new(RpcAggregatorWithResult::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    ));
+
+    let caller = {
+        let aggregator = aggregator.clone();
+        tokio::spawn(
+            async move { execute_multi_requests_with_result(&aggregator, vec![1, 2]).await },
+        )
+    };
+
+    wait_until(|| aggregator.get_count() == 1).await;
+
+    let recorder = create_recorder();
+    start_multiplying(&aggregator, &recorder).await;
+
+    assert_eq!(caller.await.unwrap().unwrap(), vec![10, 20]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_results_are_routed_to_concurrent_callers() {
+    let aggregator = Arc::new(RpcAggregatorWithResult::new(
+        "test".to_string(),
+        7,
+        create_app_states(),
+        create_logger(),
+    ));
+
+    let recorder = create_recorder();
+    start_multiplying(&aggregator, &recorder).await;
+
+    let mut callers = Vec::new();
+    for caller_no in 0..200u32 {
+        let aggregator = aggregator.clone();
+        callers.push(tokio::spawn(async move {
+            let items: Vec<u32> = (0..caller_no % 5 + 1)
+                .map(|item| caller_no * 100 + item)
+                .collect();
+            let results = execute_multi_requests_with_result(&aggregator, items.clone())
+                .await
+                .unwrap();
+            (items, results)
+        }));
+    }
+
+    for caller in callers {
+        let (items, results) = caller.await.unwrap();
+        let expected: Vec<u32> = items.iter().map(|item| item * 10).collect();
+        assert_eq!(results, expected);
+    }
+
+    assert_eq!(
+        recorder.get_items().len(),
+        (0..200).map(|i| i % 5 + 1).sum::<u32>() as usize
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_single_item_requests_are_split_by_max_amount() {
+    let aggregator = Arc::new(
+        RpcAggregatorWithResult::builder("test".to_string(), create_app_states(), create_logger())
+            .max_amount_per_round_trip(5)
+            .linger(Duration::from_millis(100))
+            .build()
+            .unwrap(),
+    );
+
+    let recorder = create_recorder();
+    start_multiplying(&aggregator, &recorder).await;
+
+    let mut callers = Vec::new();
+    for item in 0..12u32 {
+        let aggregator = aggregator.clone();
+        callers.push(tokio::spawn(async move {
+            execute_request_with_result(&aggregator, item).await
+        }));
+    }
+
+    for (item, caller) in callers.into_iter().enumerate() {
+        assert_eq!(caller.await.unwrap().unwrap(), item as u32 * 10);
+    }
+
+    let sizes: Vec<usize> = recorder
+        .get_batches()
+        .iter()
+        .map(|batch| batch.len())
+        .collect();
+    assert_eq!(sizes, vec![5, 5, 2]);
+}
+
+#[tokio::test]
+async fn test_owned_closure_returns_results() {
+    let aggregator = RpcAggregatorWithResult::<u32, u32, String>::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    );
+
+    aggregator
+        .start_with_fn(|items: Vec<u32>| async move {
+            Ok(items.into_iter().map(|item| item * 10).collect())
+        })
+        .await;
+
+    let result = execute_multi_requests_with_result(&aggregator, vec![1, 2, 3]).await;
+    assert_eq!(result.unwrap(), vec![10, 20, 30]);
+}
+
+#[tokio::test]
+async fn test_error_is_returned_to_every_caller() {
+    let aggregator = RpcAggregatorWithResult::<u32, u32, String>::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    );
+
+    aggregator
+        .start_with_lease_fn(|_items: ItemsLease<u32>| async move { Err("Failed".to_string()) })
+        .await;
+
+    let err = execute_multi_requests_with_result(&aggregator, vec![1, 2, 3])
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_str(), "Failed");
+    assert_eq!(aggregator.get_metrics().batches_failed, 1);
+}
+
+#[tokio::test]
+async fn test_callers_panic_on_wrong_amount_of_results() {
+    let aggregator = Arc::new(RpcAggregatorWithResult::<u32, u32, String>::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    ));
+
+    aggregator
+        .start_with_lease_fn(|items: ItemsLease<u32>| async move {
+            Ok(items.iter().skip(1).copied().collect())
+        })
+        .await;
+
+    let caller = {
+        let aggregator = aggregator.clone();
+        tokio::spawn(
+            async move { execute_multi_requests_with_result(&aggregator, vec![1, 2]).await },
+        )
+    };
+
+    assert!(caller.await.unwrap_err().is_panic());
+
+    let status = aggregator.status();
+    assert_eq!(
+        status.last_error.unwrap().message,
+        "Attempt 1. amount of results [1] != amount of requests [2]"
+    );
+}
+
+#[tokio::test]
+async fn test_transformation_is_applied_to_result() {
+    let aggregator =
+        RpcAggregatorWithResult::new("test".to_string(), 10, create_app_states(), create_logger());
+
+    let recorder = create_recorder();
+    start_multiplying(&aggregator, &recorder).await;
+
+    let result = aggregator
+        .execute_request_with_transformation(
+            4,
+            #[cfg(feature = "with-telemetry")]
+            common::create_telemetry(),
+            |result| format!("result {}", result),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result, "result 40");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timed_out_attempt_is_retried_with_the_same_items() {
+    let aggregator = RpcAggregatorWithResult::<u32, u32, String>::builder(
+        "test".to_string(),
+        create_app_states(),
+        create_logger(),
+    )
+    .tick_timeout(Duration::from_secs(1))
+    .retry_policy(RetryPolicy::new(3, Duration::from_millis(10)))
+    .build()
+    .unwrap();
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Hang);
+    start_multiplying(&aggregator, &recorder).await;
+
+    let started = tokio::time::Instant::now();
+    assert_eq!(
+        execute_request_with_result(&aggregator, 3).await.unwrap(),
+        30
+    );
+
+    assert!(started.elapsed() >= Duration::from_millis(1010));
+    assert_eq!(recorder.get_batches(), vec![vec![3], vec![3]]);
+    assert_eq!(aggregator.get_metrics().timeouts, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_caller_gets_result_after_panic_is_retried() {
+    let aggregator = RpcAggregatorWithResult::<u32, u32, String>::builder(
+        "test".to_string(),
+        create_app_states(),
+        create_logger(),
+    )
+    .retry_policy(RetryPolicy::new(3, Duration::from_millis(10)))
+    .build()
+    .unwrap();
+
+    let recorder = create_recorder();
+    recorder.push_behavior(MockBehavior::Panic("First attempt".to_string()));
+    start_multiplying(&aggregator, &recorder).await;
+
+    assert_eq!(
+        execute_multi_requests_with_result(&aggregator, vec![1, 2])
+            .await
+            .unwrap(),
+        vec![10, 20]
+    );
+
+    assert_eq!(recorder.get_batches(), vec![vec![1, 2], vec![1, 2]]);
+
+    let metrics = aggregator.get_metrics();
+    assert_eq!(metrics.panics, 1);
+    assert_eq!(metrics.retries, 1);
+}
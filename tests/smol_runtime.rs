@@ -0,0 +1,51 @@
+#![cfg(feature = "with-smol")]
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    create_app_states, create_logger, create_recorder, execute_multi_requests_with_result,
+};
+use rpc_aggregator::{
+    testing::MockBehavior, ItemsLease, RetryPolicy, RpcAggregatorWithResult, SmolRuntime,
+};
+
+// No tokio runtime is running here. Callers are awaited and callbacks are spawned on smol.
+#[test]
+fn test_aggregator_runs_on_smol() {
+    smol::block_on(async {
+        let aggregator: RpcAggregatorWithResult<u32, u32, String> =
+            RpcAggregatorWithResult::builder(
+                "test".to_string(),
+                create_app_states(),
+                create_logger(),
+            )
+            .max_amount_per_round_trip(10)
+            .tick_timeout(Duration::from_millis(50))
+            .retry_policy(RetryPolicy::new(2, Duration::from_millis(10)))
+            .runtime(Arc::new(SmolRuntime))
+            .build()
+            .unwrap();
+
+        // The first attempt hangs until it times out and is retried
+        let recorder = create_recorder();
+        recorder.push_behavior(MockBehavior::Hang);
+        let callback_recorder = recorder.clone();
+        aggregator
+            .start_with_lease_fn(move |items: ItemsLease<u32>| {
+                let recorder = callback_recorder.clone();
+                async move {
+                    recorder.record(&items).await?;
+                    Ok(items.iter().map(|item| item * 10).collect())
+                }
+            })
+            .await;
+
+        let result = execute_multi_requests_with_result(&aggregator, vec![1, 2, 3]).await;
+
+        assert_eq!(result.unwrap(), vec![10, 20, 30]);
+        assert_eq!(recorder.get_batches(), vec![vec![1, 2, 3], vec![1, 2, 3]]);
+        assert_eq!(aggregator.get_metrics().timeouts, 1);
+    });
+}
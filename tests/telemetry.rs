@@ -0,0 +1,76 @@
+#![cfg(feature = "with-telemetry")]
+
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use common::{
+    create_app_states, create_logger, create_recorder, execute_multi_requests_with_result,
+    execute_request,
+};
+use my_telemetry::MyTelemetryContext;
+use rpc_aggregator::{ItemsLease, RpcAggregator, RpcAggregatorCallback, RpcAggregatorWithResult};
+
+struct TelemetryCallback {
+    calls: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl RpcAggregatorCallback<u32, String> for TelemetryCallback {
+    async fn handle(
+        &self,
+        items: &[u32],
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.calls.fetch_add(items.len(), Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_callback_gets_batch_telemetry() {
+    let aggregator =
+        RpcAggregator::new("test".to_string(), 10, create_app_states(), create_logger());
+
+    let callback = Arc::new(TelemetryCallback {
+        calls: AtomicUsize::new(0),
+    });
+    aggregator.start(callback.clone()).await;
+
+    execute_request(&aggregator, 1).await.unwrap();
+
+    assert_eq!(callback.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_lease_carries_batch_telemetry() {
+    let aggregator = RpcAggregatorWithResult::<u32, u32, String>::new(
+        "test".to_string(),
+        10,
+        create_app_states(),
+        create_logger(),
+    );
+
+    let recorder = create_recorder();
+    let callback_recorder = recorder.clone();
+    aggregator
+        .start_with_lease_fn(move |items: ItemsLease<u32>| {
+            let recorder = callback_recorder.clone();
+            async move {
+                let _my_telemetry: &MyTelemetryContext = items.get_telemetry();
+                recorder.record(&items).await?;
+                Ok(items.iter().map(|item| item + 1).collect())
+            }
+        })
+        .await;
+
+    let results = execute_multi_requests_with_result(&aggregator, vec![1, 2])
+        .await
+        .unwrap();
+
+    assert_eq!(results, vec![2, 3]);
+    assert_eq!(recorder.get_calls_count(), 1);
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "with-tower")]
+
+mod common;
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use common::{create_app_states, create_logger, create_recorder};
+use rpc_aggregator::{testing::MockRecorder, AggregatorSettings, RpcAggregatorLayer};
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[derive(Clone)]
+struct RecordingService {
+    recorder: Arc<MockRecorder<u32>>,
+}
+
+impl Service<Vec<u32>> for RecordingService {
+    type Response = Vec<u32>;
+    type Error = String;
+    type Future = Pin<Box<dyn Future<Output = Result<Vec<u32>, String>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), String>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, items: Vec<u32>) -> Self::Future {
+        let recorder = self.recorder.clone();
+        Box::pin(async move {
+            recorder.record(&items).await?;
+            Ok(items.into_iter().map(|item| item + 100).collect())
+        })
+    }
+}
+
+async fn call(
+    mut service: impl Service<u32, Response = u32, Error = Arc<String>>,
+    item: u32,
+) -> u32 {
+    std::future::poll_fn(|cx| service.poll_ready(cx))
+        .await
+        .unwrap();
+    service.call(item).await.unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_every_layered_service_gets_its_own_aggregator() {
+    let mut settings = AggregatorSettings::new(10);
+    settings.linger = Duration::from_millis(100);
+
+    let layer = RpcAggregatorLayer::new(
+        "test".to_string(),
+        settings,
+        create_app_states(),
+        create_logger(),
+        #[cfg(feature = "with-telemetry")]
+        common::create_telemetry(),
+    )
+    .unwrap();
+
+    let first_recorder = create_recorder();
+    let second_recorder = create_recorder();
+    let first = layer.layer(RecordingService {
+        recorder: first_recorder.clone(),
+    });
+    let second = layer.layer(RecordingService {
+        recorder: second_recorder.clone(),
+    });
+
+    let (first_result, first_clone_result, second_result) =
+        tokio::join!(call(first.clone(), 1), call(first, 2), call(second, 3));
+
+    assert_eq!(
+        (first_result, first_clone_result, second_result),
+        (101, 102, 103)
+    );
+    assert_eq!(first_recorder.get_batches(), vec![vec![1, 2]]);
+    assert_eq!(second_recorder.get_batches(), vec![vec![3]]);
+}